@@ -1,6 +1,6 @@
 use std::{mem::take, sync::{Arc, Mutex}};
 
-        use crate::{interfaces::{Component, ComponentBuilder, EVENT}, styles::{CSSStyle, Style, STYLE}, LOGLn};
+        use crate::{interfaces::{Component, ComponentBuilder, ElementRef, EVENT}, styles::{CSSStyle, Style, STYLE}, LOGLn};
 use super::view::View;
 
 /* Button 
@@ -9,7 +9,8 @@ use super::view::View;
 pub struct Button{
     child: Arc<Mutex<dyn Component>>,
     style: Style,
-    key: Option<String>
+    key: Option<String>,
+    element_ref: Option<ElementRef>
 }
 
 impl Component for Button {
@@ -21,8 +22,13 @@ impl Component for Button {
         );
 
         let view = binding.assign_style(take(&mut self.style));
-        
-        let mview = take(view).build();
+
+        let mut view = take(view);
+        if let Some(element_ref) = &self.element_ref {
+            view = view.with_ref(element_ref);
+        }
+
+        let mview = view.build();
         
         mview
     }
@@ -39,7 +45,8 @@ impl Button {
         let mut btn = Button {
             key: key,
             child: child,
-            style: style_obj
+            style: style_obj,
+            element_ref: None
         };
         btn = btn.onclick(onclick, false);
         btn.style.onenter = btn.style.onclick_bubble.clone();
@@ -51,7 +58,8 @@ impl Button {
         let mut btn = Button {
             key: None,
             child: child,
-            style: style_obj
+            style: style_obj,
+            element_ref: None
         };
         btn = btn.onclick(onclick, false);
         btn.style.onenter = btn.style.onclick_bubble.clone();
@@ -63,38 +71,21 @@ impl Button {
         let mut btn = Button {
             key: Some(key),
             child: child,
-            style: style_obj
+            style: style_obj,
+            element_ref: None
         };
         btn = btn.onclick(onclick, false);
         btn.style.onenter = btn.style.onclick_bubble.clone();
         btn
     }
-    pub fn onclick<T: FnMut(&mut EVENT) + 'static>(mut self, onclick: T, capture:bool) -> Self {
-        if capture {
-            self.style.onclick_capture = Some(Arc::new(Mutex::new(onclick)));
-        } else {
-            self.style.onclick_bubble = Some(Arc::new(Mutex::new(onclick)));
-        }
-        self
-    }
-    pub fn onscroll<S: FnMut(&mut EVENT) + 'static>(mut self, onscroll: S, capture:bool) -> Self {
-        if capture {
-            self.style.onscroll_capture = Some(Arc::new(Mutex::new(onscroll)));
-        } else {   
-            self.style.onscroll_bubble = Some(Arc::new(Mutex::new(onscroll)));
-        }
-        self
-    }
-    pub fn onfocus<T: FnMut(&mut EVENT) + 'static>(mut self, onfocus: T) -> Self {
-        self.style.onfocus = Some(Arc::new(Mutex::new(onfocus)));
-        self
-    }
-    pub fn onunfocus<S: FnMut(&mut EVENT) + 'static>(mut self, onunfocus: S) -> Self {
-        self.style.onunfocus = Some(Arc::new(Mutex::new(onunfocus)));
+    /** Points the ref to the View of this Button */
+    pub fn with_ref(mut self, element_ref: &ElementRef) -> Self {
+        self.element_ref = Some(element_ref.clone());
         self
     }
-    pub fn onenter<S: FnMut(&mut EVENT) + 'static>(mut self, onenter: S) -> Self {
-        self.style.onenter = Some(Arc::new(Mutex::new(onenter)));
+    handler_builders!{}
+    fn edit_style(mut self, edit: impl FnOnce(&mut Style)) -> Self {
+        edit(&mut self.style);
         self
     }
 }
\ No newline at end of file
@@ -0,0 +1,56 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{interfaces::{Component, FocusScopeState}, keymap::Keymap, styles::{CSSStyle, STYLE}, IView, IViewContent};
+
+/* FocusScope
+ A View which owns the keyboard focus while mounted. Eg: a dialog or a menu.
+ Tab cycles only through the focusable elements inside it and its first focusable element gets the focus when mounted.
+ On unmount, the element focused before it was mounted gets the focus back.
+ If FocusScopes are nested, the last mounted one is used.
+*/
+pub struct FocusScope{
+    base_component:         Arc<Mutex<IView>>,
+    key:                    Option<String>
+}
+
+impl Component for FocusScope {
+    fn __call__(&mut self) -> Arc<Mutex<dyn Component>>  {
+        panic!("Invalid call to BaseComponent")
+    }
+    fn __base__(&self) -> Option<Arc<Mutex<IView>>> {
+        Some(self.base_component.clone())
+    }
+    fn __key__(&self) -> Option<String> {
+        self.key.clone()
+    }
+}
+
+impl FocusScope {
+    pub fn new(children: Vec<Arc<Mutex<dyn Component>>>, style: CSSStyle) -> FocusScope {
+        FocusScope::new_key(None, children, style)
+    }
+    pub fn new_key(key: Option<String>, children: Vec<Arc<Mutex<dyn Component>>>, style: CSSStyle) -> FocusScope {
+        FocusScope::from_iview(key, IView::with_style(style, IViewContent::CHIDREN(vec![]), children))
+    }
+    pub fn new_style_vec(children: Vec<Arc<Mutex<dyn Component>>>, style: Vec<STYLE>) -> FocusScope {
+        FocusScope::new_key_style_vec(None, children, style)
+    }
+    pub fn new_key_style_vec(key: Option<String>, children: Vec<Arc<Mutex<dyn Component>>>, style: Vec<STYLE>) -> FocusScope {
+        FocusScope::from_iview(key, IView::with_style_vec(style, IViewContent::CHIDREN(vec![]), children))
+    }
+    /** Bindings active while an element inside the scope is focused */
+    pub fn keymap(self, keymap: Keymap) -> Self {
+        self.base_component.lock().unwrap().keymap = Some(keymap);
+        self
+    }
+    fn from_iview(key: Option<String>, mut iview: IView) -> FocusScope {
+        iview.focus_scope = Some(Arc::new(Mutex::new(FocusScopeState {
+            members: vec![],
+            restore_id: -1,
+        })));
+        FocusScope {
+            key: key,
+            base_component: iview.build()
+        }
+    }
+}
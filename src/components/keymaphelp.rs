@@ -0,0 +1,68 @@
+use std::sync::{Arc, Mutex};
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::{active_bindings, interfaces::{Component, ComponentBuilder}, styles::{CSSStyle, Style, STYLE, WHITESPACE}};
+
+use super::{text::Text, view::View};
+
+/* KeymapHelp
+ Lists the active key bindings, one per line: keys followed by the description.
+ Bindings are the ones active when it is rendered, hence render it again (Eg: toggle it from a binding) to refresh the list.
+*/
+pub struct KeymapHelp{
+    style: Style,
+    key: Option<String>
+}
+
+impl Component for KeymapHelp {
+    fn __call__(&mut self) -> Arc<Mutex<dyn Component>>  {
+        let bindings = active_bindings();
+        let width = bindings.iter().map(|(keys, _)| keys.width()).max().unwrap_or(0);
+        let rows = bindings
+            .into_iter()
+            .map(|(keys, description)| {
+                let padding = " ".repeat(width - keys.width());
+                Text::new_style_vec(
+                    format!("{keys}{padding}  {description}"),
+                    vec![STYLE::WHITESPACE(WHITESPACE::NOWRAP)],
+                )
+                .build()
+            })
+            .collect();
+
+        let mut view = View::new_key_style_vec(self.key.clone(), rows, vec![]);
+        view.assign_style(self.style.clone());
+        view.build()
+    }
+    fn __key__(&self) -> Option<String> {
+        self.key.clone()
+    }
+}
+
+impl KeymapHelp {
+    pub fn new(style: CSSStyle) -> KeymapHelp {
+        KeymapHelp {
+            key: None,
+            style: style.create_style(),
+        }
+    }
+    pub fn new_key(key: Option<String>, style: CSSStyle) -> KeymapHelp {
+        KeymapHelp {
+            key: key,
+            style: style.create_style(),
+        }
+    }
+    pub fn new_style_vec(style: Vec<STYLE>) -> KeymapHelp {
+        KeymapHelp {
+            key: None,
+            style: Style::from_style(style),
+        }
+    }
+    pub fn new_key_style_vec(key: Option<String>, style: Vec<STYLE>) -> KeymapHelp {
+        KeymapHelp {
+            key: key,
+            style: Style::from_style(style),
+        }
+    }
+}
@@ -1,3 +1,76 @@
+/**
+ * Generates the event handler builders of a component.
+ * The component gives `fn edit_style(self, edit: impl FnOnce(&mut Style)) -> Self` which applies the edit to its style
+ */
+macro_rules! handler_builders {
+    () => {
+        handler_builders!{@phased
+            /** Called when the element is clicked */
+            onclick => onclick_capture, onclick_bubble;
+            /** Called when the mouse wheel scrolls over this element */
+            onscroll => onscroll_capture, onscroll_bubble;
+            /** Called for every key while this element or one inside it is focused */
+            onkeydown => onkeydown_capture, onkeydown_bubble;
+            /** Called with the pasted text while this element or one inside it is focused. See EVENT::get_paste */
+            onpaste => onpaste_capture, onpaste_bubble;
+            /** Called whenever the mouse moves over this element */
+            onmousemove => onmousemove_capture, onmousemove_bubble;
+            /** Called when any mouse button is pressed over this element */
+            onmousedown => onmousedown_capture, onmousedown_bubble;
+            /** Called when any mouse button is released over this element */
+            onmouseup => onmouseup_capture, onmouseup_bubble;
+            /** Called on a right click */
+            oncontextmenu => oncontextmenu_capture, oncontextmenu_bubble;
+            /** Called on the second of two left clicks made quickly at the same place, after its onclick */
+            ondoubleclick => ondoubleclick_capture, ondoubleclick_bubble;
+        }
+        handler_builders!{@single
+            /** Called when the mouse comes over this element. Does not bubble */
+            onmouseenter;
+            /** Called when the mouse leaves this element. Does not bubble */
+            onmouseleave;
+            /** Called when the mouse first moves while the left button pressed on this element is held */
+            ondragstart;
+            /** Called on every mouse movement of the drag. get_startx / get_starty give where it started */
+            ondrag;
+            /** Called when the left button is released after a drag */
+            ondragend;
+            /** Called when this element gets the focus */
+            onfocus;
+            /** Called when this element loses the focus */
+            onunfocus;
+            /** Called when Enter is pressed while this element is focused */
+            onenter;
+        }
+    };
+    (@phased $($(#[$doc:meta])* $name:ident => $capture:ident, $bubble:ident;)*) => {
+        $(
+            $(#[$doc])*
+            pub fn $name<H: FnMut(&mut $crate::interfaces::EVENT) + 'static>(self, handler: H, capture: bool) -> Self {
+                self.edit_style(|style| {
+                    let handler = Some(::std::sync::Arc::new(::std::sync::Mutex::new(handler)) as _);
+                    if capture {
+                        style.$capture = handler;
+                    } else {
+                        style.$bubble = handler;
+                    }
+                })
+            }
+        )*
+    };
+    (@single $($(#[$doc:meta])* $name:ident;)*) => {
+        $(
+            $(#[$doc])*
+            pub fn $name<H: FnMut(&mut $crate::interfaces::EVENT) + 'static>(self, handler: H) -> Self {
+                self.edit_style(|style| style.$name = Some(::std::sync::Arc::new(::std::sync::Mutex::new(handler))))
+            }
+        )*
+    };
+}
+
 pub mod button;
 pub mod view;
-pub mod text;
\ No newline at end of file
+pub mod text;
+pub mod richtext;
+pub mod focusscope;
+pub mod keymaphelp;
@@ -0,0 +1,114 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{interfaces::{Component, Document, ElementRef}, styles::{CSSStyle, Style, STYLE, TRANSPARENT}, IView, IViewContent};
+
+/* Span
+ A piece of text with its own color and text attributes.
+ Color, background color and attributes which are not given are taken from the RichText holding it.
+ Any other style is neglected
+*/
+#[derive(Clone)]
+pub struct Span {
+    pub(crate) text: String,
+    pub(crate) style: Style,
+}
+
+impl Span {
+    pub fn new(text: &str, style: CSSStyle) -> Span {
+        let mut style_obj = style.create_style();
+        if style.color.is_empty() {
+            style_obj.color = TRANSPARENT;
+        }
+        if style.background_color.is_empty() {
+            style_obj.background_color = TRANSPARENT;
+        }
+        Span {
+            text: text.to_string(),
+            style: style_obj
+        }
+    }
+    pub fn new_style_vec(text: &str, style: Vec<STYLE>) -> Span {
+        let mut styles = vec![STYLE::TEXTCOLOR(TRANSPARENT), STYLE::BACKGROUNDCOLOR(TRANSPARENT)];
+        styles.extend(style);
+        Span {
+            text: text.to_string(),
+            style: Style::from_style(styles)
+        }
+    }
+    /** Span which only has the style of RichText holding it */
+    pub fn plain(text: &str) -> Span {
+        Span::new_style_vec(text, vec![])
+    }
+    /** returns the color pair and attributes after taking the ones not given from the holder */
+    pub(crate) fn resolve(&self, document: &mut Document, color: i16, background_color: i16, attributes: u32) -> (i16, u32) {
+        let theme = document.theme;
+        let pair = document.get_color_pair(
+            theme.inherit(self.style.color, color),
+            theme.inherit(self.style.background_color, background_color),
+        );
+        (pair, self.style.inherit_attributes(attributes))
+    }
+}
+
+/* RichText
+ Text made of multiple Spans. Laid out and wrapped as one text block
+*/
+pub struct RichText{
+    base_component:         Arc<Mutex<IView>>,
+    key:                    Option<String>
+}
+
+impl Component for RichText {
+    fn __call__(&mut self) -> Arc<Mutex<dyn Component>>  {
+        panic!("Invalid call to BaseComponent")
+    }
+    fn __base__(&self) -> Option<Arc<Mutex<IView>>> {
+        Some(self.base_component.clone())
+    }
+    fn __key__(&self) -> Option<String> {
+        self.key.clone()
+    }
+}
+
+/** text of all the spans joined */
+fn join_spans(spans: &[Span]) -> String {
+    spans.iter().map(|span| span.text.as_str()).collect()
+}
+
+impl RichText {
+    pub fn new(spans: Vec<Span>, style: CSSStyle) -> RichText {
+        RichText::new_key(None, spans, style)
+    }
+    pub fn new_key(key: Option<String>, spans: Vec<Span>, style: CSSStyle) -> RichText {
+        let mut iview = IView::with_style(style, IViewContent::TEXT(join_spans(&spans)), vec![]);
+        iview.spans = spans;
+        RichText {
+            key: key,
+            base_component: iview.build()
+        }
+    }
+    pub fn new_style_vec(spans: Vec<Span>, style: Vec<STYLE>) -> RichText {
+        RichText::new_key_style_vec(None, spans, style)
+    }
+    pub fn new_key_style_vec(key: Option<String>, spans: Vec<Span>, style: Vec<STYLE>) -> RichText {
+        let mut iview = IView::from_text(join_spans(&spans), style);
+        iview.spans = spans;
+        RichText {
+            key: key,
+            base_component: iview.build()
+        }
+    }
+    /** Points the ref to this element */
+    pub fn with_ref(self, element_ref: &ElementRef) -> Self {
+        element_ref.attach(&self.base_component);
+        self
+    }
+    pub fn is_focused(&self) -> bool {
+        self.base_component.lock().unwrap().focused
+    }
+    handler_builders!{}
+    fn edit_style(self, edit: impl FnOnce(&mut Style)) -> Self {
+        edit(&mut self.base_component.lock().unwrap().style);
+        self
+    }
+}
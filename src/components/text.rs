@@ -1,6 +1,6 @@
 use std::sync::{Arc, Mutex};
 
-use crate::{interfaces::{Component, EVENT}, styles::{CSSStyle, STYLE}, IView};
+use crate::{interfaces::{Component, ElementRef}, styles::{CSSStyle, Style, STYLE}, IView};
 
 /* Text 
  Basic Text which can hold an string
@@ -51,35 +51,17 @@ impl Text {
             base_component: iview.build()
         }
     }
-    pub fn is_focused(&self) -> bool {
-        self.base_component.lock().unwrap().focused
-    }
-    pub fn onclick<T: FnMut(&mut EVENT) + 'static>(self, onclick: T, capture:bool) -> Self {
-        if capture {
-            self.base_component.lock().unwrap().style.onclick_capture = Some(Arc::new(Mutex::new(onclick)));
-        } else {
-            self.base_component.lock().unwrap().style.onclick_bubble = Some(Arc::new(Mutex::new(onclick)));
-        }
-        self
-    }
-    pub fn onscroll<S: FnMut(&mut EVENT) + 'static>(self, onscroll: S, capture:bool) -> Self {
-        if capture {
-            self.base_component.lock().unwrap().style.onscroll_capture = Some(Arc::new(Mutex::new(onscroll)));
-        } else {   
-            self.base_component.lock().unwrap().style.onscroll_bubble = Some(Arc::new(Mutex::new(onscroll)));
-        }
-        self
-    }
-    pub fn onfocus<T: FnMut(&mut EVENT) + 'static>(self, onfocus: T) -> Self {
-        self.base_component.lock().unwrap().style.onfocus = Some(Arc::new(Mutex::new(onfocus)));
+    /** Points the ref to this element */
+    pub fn with_ref(self, element_ref: &ElementRef) -> Self {
+        element_ref.attach(&self.base_component);
         self
     }
-    pub fn onunfocus<S: FnMut(&mut EVENT) + 'static>(self, onunfocus: S) -> Self {
-        self.base_component.lock().unwrap().style.onunfocus = Some(Arc::new(Mutex::new(onunfocus)));
-        self
+    pub fn is_focused(&self) -> bool {
+        self.base_component.lock().unwrap().focused
     }
-    pub fn onenter<S: FnMut(&mut EVENT) + 'static>(self, onenter: S) -> Self {
-        self.base_component.lock().unwrap().style.onenter = Some(Arc::new(Mutex::new(onenter)));
+    handler_builders!{}
+    fn edit_style(self, edit: impl FnOnce(&mut Style)) -> Self {
+        edit(&mut self.base_component.lock().unwrap().style);
         self
     }
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
 use std::{sync::{Arc, Mutex}};
 
-use crate::{interfaces::{Component, EVENT}, styles::{CSSStyle, Style, STYLE}, IView, IViewContent};
+use crate::{interfaces::{Component, ElementRef, ScrollHandle, EVENT}, keymap::Keymap, styles::{CSSStyle, Style, STYLE, TEXTALIGN}, IView, IViewContent};
+
+use super::richtext::Span;
 
 
 
@@ -54,32 +56,38 @@ impl View {
     pub fn is_focused(&self) -> bool {
         self.base_component.lock().unwrap().focused
     }
-    pub fn onclick<T: FnMut(&mut EVENT) + 'static>(self, onclick: T, capture:bool) -> Self {
-        if capture {
-            self.base_component.lock().unwrap().style.onclick_capture = Some(Arc::new(Mutex::new(onclick)));
-        } else {
-            self.base_component.lock().unwrap().style.onclick_bubble = Some(Arc::new(Mutex::new(onclick)));
-        }
+    handler_builders!{}
+    fn edit_style(self, edit: impl FnOnce(&mut Style)) -> Self {
+        edit(&mut self.base_component.lock().unwrap().style);
         self
     }
-    pub fn onscroll<S: FnMut(&mut EVENT) + 'static>(self, onscroll: S, capture:bool) -> Self {
-        if capture {
-            self.base_component.lock().unwrap().style.onscroll_capture = Some(Arc::new(Mutex::new(onscroll)));
-        } else {   
-            self.base_component.lock().unwrap().style.onscroll_bubble = Some(Arc::new(Mutex::new(onscroll)));
-        }
+    /** Called with the new size after the layout in which the size of this element changed. See EVENT::get_height, get_width */
+    pub fn onresize<R: FnMut(&mut EVENT) + 'static>(self, onresize: R) -> Self {
+        self.edit_style(|style| style.onresize = Some(Arc::new(Mutex::new(onresize))))
+    }
+    /** Caption drawn over the top border. Needs the top border */
+    pub fn border_title(self, title: Span, align: TEXTALIGN) -> Self {
+        self.base_component.lock().unwrap().style.set_style(STYLE::BORDERTITLE(title, align));
+        self
+    }
+    /** Caption drawn over the bottom border. Needs the bottom border */
+    pub fn border_footer(self, footer: Span, align: TEXTALIGN) -> Self {
+        self.base_component.lock().unwrap().style.set_style(STYLE::BORDERFOOTER(footer, align));
         self
     }
-    pub fn onfocus<T: FnMut(&mut EVENT) + 'static>(self, onfocus: T) -> Self {
-        self.base_component.lock().unwrap().style.onfocus = Some(Arc::new(Mutex::new(onfocus)));
+    /** Lets the handle scroll this View and read its scroll offsets */
+    pub fn scroll_handle(self, handle: &ScrollHandle) -> Self {
+        handle.attach(&self.base_component);
         self
     }
-    pub fn onunfocus<S: FnMut(&mut EVENT) + 'static>(self, onunfocus: S) -> Self {
-        self.base_component.lock().unwrap().style.onunfocus = Some(Arc::new(Mutex::new(onunfocus)));
+    /** Bindings active while this View or an element inside it is focused */
+    pub fn keymap(self, keymap: Keymap) -> Self {
+        self.base_component.lock().unwrap().keymap = Some(keymap);
         self
     }
-    pub fn onenter<S: FnMut(&mut EVENT) + 'static>(self, onenter: S) -> Self {
-        self.base_component.lock().unwrap().style.onenter = Some(Arc::new(Mutex::new(onenter)));
+    /** Points the ref to this View */
+    pub fn with_ref(self, element_ref: &ElementRef) -> Self {
+        element_ref.attach(&self.base_component);
         self
     }
     pub(crate) fn assign_style(&mut self, style_obj: Style) -> &mut Self {
@@ -2,13 +2,16 @@ use std::{
     any::Any,
     clone,
     collections::HashMap,
-    sync::{Arc, LazyLock, Mutex},
+    fmt::Debug,
+    ops::BitOr,
+    sync::{Arc, LazyLock, Mutex, Weak},
+    time::Instant,
 };
 
 use dyn_clone::DynClone;
-use ncurses::{MENU, MEVENT, PANEL, WINDOW, init_pair, newwin};
+use ncurses::{MENU, MEVENT, PANEL, WINDOW, can_change_color, init_color, init_pair, newwin};
 
-use crate::{_debug_iview, LOGLn, nmodels::iview::IView};
+use crate::{_debug_iview, DOCUMENT, LOGLn, keymap::Keymap, nmodels::iview::IView, theme::Theme};
 
 pub trait StateEqual {
     fn equal(&self, other: &Self) -> bool;
@@ -92,14 +95,112 @@ pub trait ComponentBuilder<T> {
     fn build(self) -> Arc<Mutex<dyn Component>>;
 }
 
+/** Key of a keyboard event, independent of the modifiers held with it */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /** Printable character. Shift of a letter is in the character itself, Eg: 'A' */
+    Char(char),
+    /** Function key F1, F2, .. */
+    F(u8),
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    Backspace,
+    Enter,
+    Tab,
+    Esc,
+    /** Code from getch which could not be decoded */
+    Unknown(i32),
+}
+
+/** Set of the modifier keys held with a key. Combine using `|` */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers(pub(crate) u8);
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers(0);
+    pub const SHIFT: Modifiers = Modifiers(1);
+    pub const ALT: Modifiers = Modifiers(1 << 1);
+    pub const CTRL: Modifiers = Modifiers(1 << 2);
+
+    /** whether all the modifiers of other are held */
+    pub fn contains(self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Modifiers;
+    fn bitor(self, other: Modifiers) -> Modifiers {
+        Modifiers(self.0 | other.0)
+    }
+}
+
+/** Decoded keyboard input. Available on EVENT of keyboard events as `get_key_event()` */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub key: Key,
+    pub modifiers: Modifiers,
+}
+
+impl KeyEvent {
+    pub fn new(key: Key, modifiers: Modifiers) -> KeyEvent {
+        KeyEvent { key, modifiers }
+    }
+    pub fn ctrl(&self) -> bool {
+        self.modifiers.contains(Modifiers::CTRL)
+    }
+    pub fn alt(&self) -> bool {
+        self.modifiers.contains(Modifiers::ALT)
+    }
+    pub fn shift(&self) -> bool {
+        self.modifiers.contains(Modifiers::SHIFT)
+    }
+}
+
 #[derive(Debug)]
 pub struct EVENT {
     pub(crate) mevent: Option<MEVENT>,
     pub(crate) key: i32,
+    /** None for mouse events */
+    pub(crate) key_event: Option<KeyEvent>,
+    /** Text of a paste event */
+    pub(crate) paste: Option<String>,
     pub(crate) clientx: i32,
     pub(crate) clienty: i32,
+    /** Left clicks made in a row at the same place, 2 on a double click. 0 for other events */
+    pub(crate) click_count: i32,
+    /** Screen position where the left button went down. Set for the drag events */
+    pub(crate) startx: i32,
+    pub(crate) starty: i32,
+    /** Mouse position relative to the content box of the target, scroll included */
+    pub(crate) offsetx: i32,
+    pub(crate) offsety: i32,
+    /** Innermost element under the mouse. -1 if none */
+    pub(crate) target_id: i32,
+    pub(crate) target_key: Option<String>,
+    /** Size of the element, padding and border included. Set for onresize */
+    pub(crate) height: i32,
+    pub(crate) width: i32,
     pub(crate) propogate: bool,
     pub(crate) default: bool,
+    /** Set while the target is looked for, before any handler is called */
+    pub(crate) locating: bool,
+    /** Scroll offsets and their maximums of the element whose handler is called */
+    pub(crate) scrollx: i32,
+    pub(crate) scrolly: i32,
+    pub(crate) max_scrollx: i32,
+    pub(crate) max_scrolly: i32,
 }
 
 impl EVENT {
@@ -107,10 +208,26 @@ impl EVENT {
         EVENT {
             mevent: None,
             key: ch,
+            key_event: None,
+            paste: None,
             clientx: 0,
             clienty: 0,
+            click_count: 0,
+            startx: 0,
+            starty: 0,
+            offsetx: 0,
+            offsety: 0,
+            target_id: -1,
+            target_key: None,
+            height: 0,
+            width: 0,
             propogate: true,
             default: true,
+            locating: false,
+            scrollx: 0,
+            scrolly: 0,
+            max_scrollx: 0,
+            max_scrolly: 0,
         }
     }
 
@@ -121,12 +238,70 @@ impl EVENT {
     pub fn get_key(&self) -> i32 {
         self.key
     }
+    /** Key and modifiers of a keyboard event. None for mouse events */
+    pub fn get_key_event(&self) -> Option<KeyEvent> {
+        self.key_event
+    }
+    /** Pasted text. None for other events */
+    pub fn get_paste(&self) -> Option<String> {
+        self.paste.clone()
+    }
     pub fn get_clientx(&self) -> i32 {
         self.clientx
     }
     pub fn get_clienty(&self) -> i32 {
         self.clienty
     }
+    /** Left clicks made in a row at the same place, 2 on a double click. 0 for other events */
+    pub fn get_click_count(&self) -> i32 {
+        self.click_count
+    }
+    /** x where the drag started. clientx is where the mouse is now */
+    pub fn get_startx(&self) -> i32 {
+        self.startx
+    }
+    /** y where the drag started. clienty is where the mouse is now */
+    pub fn get_starty(&self) -> i32 {
+        self.starty
+    }
+    /** Column of the mouse inside the content of the target, counting from the start of its scrolled content */
+    pub fn get_offsetx(&self) -> i32 {
+        self.offsetx
+    }
+    /** Row of the mouse inside the content of the target, counting from the start of its scrolled content */
+    pub fn get_offsety(&self) -> i32 {
+        self.offsety
+    }
+    /** key of the Component under the mouse, which is the innermost one */
+    pub fn get_target_key(&self) -> Option<String> {
+        self.target_key.clone()
+    }
+    /** Whether the element of the ref is the innermost one under the mouse */
+    pub fn is_target(&self, element_ref: &ElementRef) -> bool {
+        self.target_id >= 0 && element_ref.state.lock().unwrap().id == self.target_id
+    }
+    /** Height of the element in onresize, padding and border included */
+    pub fn get_height(&self) -> i32 {
+        self.height
+    }
+    /** Width of the element in onresize, padding and border included */
+    pub fn get_width(&self) -> i32 {
+        self.width
+    }
+    /** horizontal scroll offset of the element. In onscroll it is the offset after scrolling */
+    pub fn get_scrollx(&self) -> i32 {
+        self.scrollx
+    }
+    /** vertical scroll offset of the element. In onscroll it is the offset after scrolling */
+    pub fn get_scrolly(&self) -> i32 {
+        self.scrolly
+    }
+    pub fn get_max_scrollx(&self) -> i32 {
+        self.max_scrollx
+    }
+    pub fn get_max_scrolly(&self) -> i32 {
+        self.max_scrolly
+    }
 
     pub fn stop_propogation(&mut self) {
         self.propogate = false;
@@ -175,9 +350,355 @@ impl Fiber {
     }
 }
 
+/** Ids returned by `Document::new_color` start from here and go downwards */
+pub(crate) const CUSTOM_COLOR_START: i16 = -100;
+
+/** rgb values of the 16 standard colors, as used by xterm */
+const STANDARD_COLORS: [(i16, i16, i16); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/** Levels of each component in the 6 * 6 * 6 box of 256 color palette */
+const CUBE_LEVELS: [i16; 6] = [0, 95, 135, 175, 215, 255];
+
+/**
+ * returns the color in the palette nearest to given rgb.
+ * 256 colors: uses the 6 * 6 * 6 box and grayscale
+ * 16 / 8 colors: uses the standard colors
+ */
+pub(crate) fn nearest_color(rgb: (i16, i16, i16), total_colors: i32) -> i16 {
+    let distance = |other: (i16, i16, i16)| {
+        let (dr, dg, db) = (
+            (rgb.0 - other.0) as i32,
+            (rgb.1 - other.1) as i32,
+            (rgb.2 - other.2) as i32,
+        );
+        dr * dr + dg * dg + db * db
+    };
+
+    let mut candidates: Vec<(i16, (i16, i16, i16))> = vec![];
+    if total_colors >= 256 {
+        for r in 0..6 {
+            for g in 0..6 {
+                for b in 0..6 {
+                    candidates.push((
+                        16 + 36 * r + 6 * g + b,
+                        (CUBE_LEVELS[r as usize], CUBE_LEVELS[g as usize], CUBE_LEVELS[b as usize]),
+                    ));
+                }
+            }
+        }
+        for i in 0..24 {
+            let level = 8 + 10 * i;
+            candidates.push((232 + i, (level, level, level)));
+        }
+    } else {
+        let total = if total_colors >= 16 { 16 } else { 8 };
+        STANDARD_COLORS
+            .iter()
+            .take(total)
+            .enumerate()
+            .for_each(|(i, c)| candidates.push((i as i16, *c)));
+    }
+
+    candidates
+        .into_iter()
+        .min_by_key(|(_, c)| distance(*c))
+        .map_or(-1, |(number, _)| number)
+}
+
+/** Scrollbar thumb being dragged with the mouse */
+#[derive(Clone)]
+pub(crate) struct ScrollDrag {
+    pub(crate) iview: Arc<Mutex<IView>>,
+    pub(crate) vertical: bool,
+    /** screen position of the mouse along the scrollbar when the drag started */
+    pub(crate) start: i32,
+    pub(crate) start_scroll: i32,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum SCROLLREQUEST {
+    /** None keeps that offset */
+    TO(Option<i32>, Option<i32>),
+    BY(i32, i32),
+    END,
+    INTOVIEW(String),
+}
+
+#[derive(Default)]
+pub(crate) struct ScrollState {
+    /** IView using the handle. Replaced whenever the View is built again */
+    pub(crate) iview: Option<Weak<Mutex<IView>>>,
+    /** applied in order after the next layout of the IView */
+    pub(crate) requests: Vec<SCROLLREQUEST>,
+    pub(crate) scrollx: i32,
+    pub(crate) scrolly: i32,
+    pub(crate) max_scrollx: i32,
+    pub(crate) max_scrolly: i32,
+}
+
+/**
+ * Scrolls a View from outside of it. Attach it using `View::scroll_handle`.
+ * Keep the same handle across renders, eg: in `use_state`.
+ * Scrolls are applied before the next render, hence they can be called from any event handler.
+ */
+#[derive(Clone, Default)]
+pub struct ScrollHandle {
+    pub(crate) state: Arc<Mutex<ScrollState>>,
+}
+
+impl PartialEq for ScrollHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl Debug for ScrollHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("ScrollHandle")
+            .field("scrolly", &state.scrolly)
+            .field("scrollx", &state.scrollx)
+            .finish()
+    }
+}
+
+impl ScrollHandle {
+    pub fn new() -> ScrollHandle {
+        ScrollHandle::default()
+    }
+
+    /**
+     * Uses DOCUMENT lock()
+     */
+    fn request(&self, request: SCROLLREQUEST) {
+        let iview = {
+            let mut state = self.state.lock().unwrap();
+            state.requests.push(request);
+            state.iview.clone()
+        };
+        let mut document = DOCUMENT.lock().unwrap();
+        if let Some(iview) = iview {
+            document.scroll_requests.push(iview);
+        }
+        document.changed = true;
+    }
+
+    /** Scrolls to the given offsets */
+    pub fn scroll_to(&self, y: i32, x: i32) {
+        self.request(SCROLLREQUEST::TO(Some(y), Some(x)));
+    }
+
+    /** Scrolls vertically to y, keeping the horizontal offset */
+    pub fn scroll_to_y(&self, y: i32) {
+        self.request(SCROLLREQUEST::TO(Some(y), None));
+    }
+
+    /** Scrolls horizontally to x, keeping the vertical offset */
+    pub fn scroll_to_x(&self, x: i32) {
+        self.request(SCROLLREQUEST::TO(None, Some(x)));
+    }
+
+    /** Scrolls by the given amount. Negative values scroll up/left */
+    pub fn scroll_by(&self, dy: i32, dx: i32) {
+        self.request(SCROLLREQUEST::BY(dy, dx));
+    }
+
+    /** Scrolls to the bottom */
+    pub fn scroll_to_end(&self) {
+        self.request(SCROLLREQUEST::END);
+    }
+
+    /** Scrolls the least amount which makes the direct child with the given key visible */
+    pub fn scroll_into_view(&self, child_key: &str) {
+        self.request(SCROLLREQUEST::INTOVIEW(child_key.to_string()));
+    }
+
+    /** Offsets and maximums after the last layout */
+    pub fn scrolly(&self) -> i32 {
+        self.state.lock().unwrap().scrolly
+    }
+    pub fn scrollx(&self) -> i32 {
+        self.state.lock().unwrap().scrollx
+    }
+    pub fn max_scrolly(&self) -> i32 {
+        self.state.lock().unwrap().max_scrolly
+    }
+    pub fn max_scrollx(&self) -> i32 {
+        self.state.lock().unwrap().max_scrollx
+    }
+
+    /** Makes the given IView use this handle */
+    pub(crate) fn attach(&self, iview_lk: &Arc<Mutex<IView>>) {
+        self.state.lock().unwrap().iview = Some(Arc::downgrade(iview_lk));
+        let mut iview = iview_lk.lock().unwrap();
+        if !iview.scroll_handles.contains(self) {
+            iview.scroll_handles.push(self.clone());
+        }
+    }
+}
+
+/** Position on the screen and size of an element */
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rect {
+    pub y: i32,
+    pub x: i32,
+    pub height: i32,
+    pub width: i32,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum FOCUSREQUEST {
+    /** id of the IView */
+    ID(i32),
+    /** removes the focus if the IView with this id has it */
+    BLUR(i32),
+    /** same as Tab */
+    NEXT,
+    /** same as Shift + Tab */
+    PREV,
+    /** first element in tab order with this key */
+    KEY(String),
+}
+
+/** Shared by the IViews a FocusScope yields across its renders */
+#[derive(Default)]
+pub(crate) struct FocusScopeState {
+    /** ids of the focusable elements inside the scope at the last render */
+    pub(crate) members: Vec<i32>,
+    /** id of the element focused when the scope was mounted. -1 if none */
+    pub(crate) restore_id: i32,
+}
+
+#[derive(Default)]
+pub(crate) struct RefState {
+    /** id of the IView it is attached to. -1 if none */
+    pub(crate) id: i32,
+    pub(crate) rect: Rect,
+    /** Document's refresh_count when the IView was last found in the tree */
+    pub(crate) generation: u32,
+}
+
+/**
+ * Handle to the element a base Component yields. Get one using `use_element_ref` and attach using `with_ref`.
+ * It follows the element when the Component is built again.
+ * Values are of the last render, hence the methods do not block even inside the element's own handlers.
+ */
+#[derive(Clone)]
+pub struct ElementRef {
+    pub(crate) state: Arc<Mutex<RefState>>,
+    scroll: ScrollHandle,
+}
+
+impl Default for ElementRef {
+    fn default() -> Self {
+        ElementRef::new()
+    }
+}
+
+impl PartialEq for ElementRef {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl Debug for ElementRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("ElementRef")
+            .field("id", &state.id)
+            .field("rect", &state.rect)
+            .finish()
+    }
+}
+
+impl ElementRef {
+    pub fn new() -> ElementRef {
+        ElementRef {
+            state: Arc::new(Mutex::new(RefState {
+                id: -1,
+                ..Default::default()
+            })),
+            scroll: ScrollHandle::new(),
+        }
+    }
+
+    /** Whether the element was in the tree at the last render. Uses DOCUMENT lock() */
+    pub fn is_mounted(&self) -> bool {
+        let generation = self.state.lock().unwrap().generation;
+        generation > 0 && generation == DOCUMENT.lock().unwrap().refresh_count
+    }
+
+    /** Position on the screen and size (with padding and border) at the last render. None if not mounted */
+    pub fn computed_rect(&self) -> Option<Rect> {
+        if !self.is_mounted() {
+            return None;
+        }
+        Some(self.state.lock().unwrap().rect)
+    }
+
+    /**
+     * Focuses the element. Only elements with taborder >= 0 can be focused.
+     * Applied after the current event. Uses DOCUMENT lock()
+     */
+    pub fn focus(&self) {
+        let id = self.state.lock().unwrap().id;
+        if id >= 0 {
+            DOCUMENT.lock().unwrap().focus_request = Some(FOCUSREQUEST::ID(id));
+        }
+    }
+
+    /** Removes the focus if the element has it. Uses DOCUMENT lock() */
+    pub fn blur(&self) {
+        let id = self.state.lock().unwrap().id;
+        if id >= 0 {
+            DOCUMENT.lock().unwrap().focus_request = Some(FOCUSREQUEST::BLUR(id));
+        }
+    }
+
+    /** Scrolls the element. See `ScrollHandle::scroll_to` */
+    pub fn scroll_to(&self, y: i32, x: i32) {
+        self.scroll.scroll_to(y, x);
+    }
+
+    /** ScrollHandle of the element, for the rest of the scroll functions */
+    pub fn scroll_handle(&self) -> ScrollHandle {
+        self.scroll.clone()
+    }
+
+    /** Makes the ref point to the given IView */
+    pub(crate) fn attach(&self, iview_lk: &Arc<Mutex<IView>>) {
+        self.scroll.attach(iview_lk);
+        let mut iview = iview_lk.lock().unwrap();
+        self.state.lock().unwrap().id = iview.id;
+        iview.element_ref = Some(self.clone());
+    }
+}
+
+/** element which had the focus and the one which has it now */
+pub(crate) type FocusChange = (Option<Arc<Mutex<IView>>>, Option<Arc<Mutex<IView>>>);
+
 pub(crate) struct TabElement {
     pub id: i32,
     pub iview: Arc<Mutex<IView>>,
+    /** key of the Component holding the IView */
+    pub key: Option<String>,
 }
 
 pub struct Document {
@@ -186,19 +707,59 @@ pub struct Document {
     pub(crate) tabindex: usize,
     pub(crate) unique_id: i32,
     pub(crate) curr_active: Option<Arc<Mutex<IView>>>,
+    /** IViews found under the mouse during the current mouse event, outermost first */
+    pub(crate) mouse_path: Vec<Arc<Mutex<IView>>>,
+    /** IViews under the mouse after the last mouse event */
+    pub(crate) hover_path: Vec<Arc<Mutex<IView>>>,
+    /** IViews on which mouse button was pressed and not yet released */
+    pub(crate) pressed_path: Vec<Arc<Mutex<IView>>>,
+    pub(crate) scroll_drag: Option<ScrollDrag>,
+    /** Screen (y, x) where the left button went down, while it is held */
+    pub(crate) press_start: Option<(i32, i32)>,
+    /** true once the mouse moved with the left button held. ondragend is due on release */
+    pub(crate) dragging: bool,
+    /** When and where the last left click happened and how many clicks in a row it made */
+    pub(crate) last_click: Option<(Instant, (i32, i32), i32)>,
+    /** IViews having scroll requests from their ScrollHandle. Laid out again on the next refresh */
+    pub(crate) scroll_requests: Vec<Weak<Mutex<IView>>>,
+    /** Focus change asked through the public api. Applied after the current event */
+    pub(crate) focus_request: Option<FOCUSREQUEST>,
+    /** Number of times the tree has been rendered. Used to know if an ElementRef is mounted */
+    pub(crate) refresh_count: u32,
+    /** Keymap of the app, set by set_keymap */
+    pub(crate) keymap: Option<Keymap>,
+    /** Keymaps on the path from the focused element to the root, innermost first, then the app's one */
+    pub(crate) active_keymaps: Vec<Keymap>,
+    /** Conflicts between the active keymaps, already logged */
+    pub(crate) keymap_conflicts: Vec<String>,
+    /** Keys of a chord typed so far */
+    pub(crate) pending_keys: Vec<KeyEvent>,
+    /** When the last key of pending_keys was typed */
+    pub(crate) pending_since: Option<Instant>,
+    /** (rows, columns) of the terminal */
+    pub(crate) terminal_size: (i32, i32),
+    /** Fibers of the Components using use_terminal_size. Rendered again on resize */
+    pub(crate) size_subscribers: Vec<Weak<Mutex<Fiber>>>,
+    /** Mounted FocusScopes, in the order they were mounted. Tab cycles inside the last one */
+    pub(crate) focus_scopes: Vec<Arc<Mutex<FocusScopeState>>>,
 
     /** If any iview calls focus then this is set to its id. used by focus */
     pub(crate) next_tab_id: i32,
 
     pub(crate) color_pairs: LazyLock<Mutex<HashMap<(i16, i16), u16>>>,
-    // pub(crate) colors: LazyLock<Mutex<HashMap<(i16, i16, i16), u16>>>,
+    /** rgb -> actual color number of the terminal */
+    pub(crate) colors: LazyLock<Mutex<HashMap<(i16, i16, i16), u16>>>,
+    /** rgb of colors created by `new_color`. Position is encoded in the id returned by `new_color` */
+    pub(crate) custom_colors: Vec<(i16, i16, i16)>,
     pub(crate) total_allowed_pairs: i32,
-    // pub(crate) max_supported_colors: i32,
-    // pub(crate) curr_color: u16,
+    pub(crate) max_supported_colors: i32,
+    /** Number of colors defined using init_color. These are taken after the 256 colors palette, which no style can name */
+    pub(crate) curr_color: u16,
     /** Used when color_pairs goes above limit(COLOR_PAIRS) */
     pub(crate) curr_color_pair: u16,
+    /** Used to resolve the color roles */
+    pub(crate) theme: Theme,
 
-    /** Used when colors goes above limit(COLORS) */
     /** turned true if some changed happen to any IView. (This may occur even though there was no state change in Fiber. Eg of such events. Scroll, Focus) */
     pub(crate) changed: bool,
 }
@@ -224,6 +785,27 @@ impl Document {
         self.curr_active = None;
     }
 
+    /** Replaces the prev IView with the new one in hover and pressed paths and the scroll drag */
+    pub(crate) fn replace_in_mouse_paths(
+        &mut self,
+        prev_iview: &Arc<Mutex<IView>>,
+        iview: &Arc<Mutex<IView>>,
+    ) {
+        self.hover_path
+            .iter_mut()
+            .chain(self.pressed_path.iter_mut())
+            .for_each(|element| {
+                if Arc::ptr_eq(element, prev_iview) {
+                    *element = iview.clone();
+                }
+            });
+        if let Some(drag) = &mut self.scroll_drag
+            && Arc::ptr_eq(&drag.iview, prev_iview)
+        {
+            drag.iview = iview.clone();
+        }
+    }
+
     /**
      * returns pair Number
      *
@@ -234,6 +816,8 @@ impl Document {
      *
      */
     pub(crate) fn get_color_pair(&mut self, color_foregorund: i16, color_background: i16) -> i16 {
+        let color_foregorund = self.terminal_color(color_foregorund);
+        let color_background = self.terminal_color(color_background);
         let mut c_pairs = self.color_pairs.lock().unwrap();
         let pair = (color_foregorund, color_background);
         if let Some(pair_no) = c_pairs.get(&pair) {
@@ -266,12 +850,85 @@ impl Document {
         return 16 + (36 * r + 6 * g + b);
     }
 
+    /**
+     * Creates a color using its rgb values and returns its id. The id can be used in place of any other color.
+     *
+     * red : 0 - 255
+     * green: 0 - 255
+     * blue: 0 - 255
+     *
+     * If terminal can change colors and has colors after the 256 color palette, the exact color is defined using init_color in one of them,
+     * else the nearest color available in the palette is used.
+     * Uses DOCUMENT.lock()
+     */
+    pub fn new_color(red: i16, green: i16, blue: i16) -> i16 {
+        let rgb = (red.clamp(0, 255), green.clamp(0, 255), blue.clamp(0, 255));
+        let mut document = DOCUMENT.lock().unwrap();
+        let idx = match document.custom_colors.iter().position(|c| *c == rgb) {
+            Some(idx) => idx,
+            // no ids left
+            None if document.custom_colors.len() > (CUSTOM_COLOR_START - i16::MIN) as usize => {
+                return nearest_color(rgb, document.max_supported_colors);
+            }
+            None => {
+                document.custom_colors.push(rgb);
+                document.custom_colors.len() - 1
+            }
+        };
+        CUSTOM_COLOR_START - idx as i16
+    }
+
+    /**
+     * returns the actual color number for the color created by `new_color`.
+     * Other colors are returned as it is.
+     */
+    pub(crate) fn terminal_color(&mut self, color: i16) -> i16 {
+        if color > CUSTOM_COLOR_START {
+            return color;
+        }
+        let Some(rgb) = self
+            .custom_colors
+            .get((CUSTOM_COLOR_START - color) as usize)
+            .copied()
+        else {
+            return -1;
+        };
+        let mut colors = self.colors.lock().unwrap();
+        if let Some(number) = colors.get(&rgb) {
+            return *number as i16;
+        }
+        // the palette is never redefined, once the colors after it run out the nearest one is used
+        let number = 256 + self.curr_color as i32;
+        let number = if can_change_color() && number < self.max_supported_colors.min(i16::MAX as i32) {
+            let scale = |c: i16| (c as i32 * 1000 / 255) as i16;
+            init_color(number as i16, scale(rgb.0), scale(rgb.1), scale(rgb.2));
+            self.curr_color += 1;
+            number as i16
+        } else {
+            nearest_color(rgb, self.max_supported_colors)
+        };
+        colors.insert(rgb, number as u16);
+        number
+    }
+
     pub(crate) fn clear_color_pairs(&mut self) {
         let mut c_pairs = self.color_pairs.lock().unwrap();
         c_pairs.clear();
         self.curr_color_pair = 0;
     }
 
+    /** Stores the size of the terminal and marks the Components using it to be rendered again */
+    pub(crate) fn resize_terminal(&mut self, size: (i32, i32)) {
+        self.terminal_size = size;
+        self.size_subscribers.retain(|fiber_lk| {
+            fiber_lk
+                .upgrade()
+                .inspect(|fiber_lk| fiber_lk.lock().unwrap().changed = true)
+                .is_some()
+        });
+        self.changed = true;
+    }
+
     /**
      * Assigns given fiber to global fiber
      * resets the head to 0
@@ -310,9 +967,10 @@ impl Document {
     }
 
     /** Uses element.lock() */
-    pub(crate) fn insert_tab_element(&mut self, element: Arc<Mutex<IView>>) {
+    pub(crate) fn insert_tab_element(&mut self, element: Arc<Mutex<IView>>, key: Option<String>) {
         let id = element.lock().unwrap().id;
-        if let Some(_) = self.taborder.iter().find(|ielement| ielement.id == id) {
+        if let Some(ielement) = self.taborder.iter_mut().find(|ielement| ielement.id == id) {
+            ielement.key = key;
             return;
         }
         // else this would pick up an element after taborder length is increase due to push
@@ -323,6 +981,7 @@ impl Document {
         self.taborder.push(TabElement {
             id: id,
             iview: element,
+            key: key,
         });
     }
 
@@ -370,6 +1029,18 @@ impl Document {
     }
     
     pub(crate) fn find_n_update(&mut self, id: i32,iview: &Arc<Mutex<IView>>) -> bool {
+        // pending focus request follows the IView too
+        if let Some(FOCUSREQUEST::ID(request_id) | FOCUSREQUEST::BLUR(request_id)) = &mut self.focus_request
+            && *request_id == id
+        {
+            *request_id = iview.lock().unwrap().id;
+        }
+        self.focus_scopes.iter().for_each(|scope| {
+            let mut scope = scope.lock().unwrap();
+            if scope.restore_id == id {
+                scope.restore_id = iview.lock().unwrap().id;
+            }
+        });
         if let Some(idx) = self.taborder.iter().position(|ielement| ielement.id == id) {
             let element = &mut self.taborder[idx];
             element.id = iview.lock().unwrap().id;
@@ -398,7 +1069,7 @@ impl Document {
     }
 
     /**Locks the iview */
-    pub(crate) fn advance_tab(&mut self) -> (Option<Arc<Mutex<IView>>>, Option<Arc<Mutex<IView>>>) {
+    pub(crate) fn advance_tab(&mut self) -> FocusChange {
         let prev_iview_lk = self.focused_element();
         // LOGLn!(
         //     "START: {} {} {}",
@@ -408,6 +1079,10 @@ impl Document {
         // );
         if self.next_tab_id != -1 {
             self.focus()
+        } else if let Some(members) = self.scope_members() {
+            let pos = members.iter().position(|&idx| idx == self.tabindex);
+            self.tabindex = members[pos.map_or(0, |pos| (pos + 1) % members.len())];
+            (prev_iview_lk, self.focused_element())
         } else {
             self.tabindex += 1;
             self.next_tab_id = -1;
@@ -419,7 +1094,7 @@ impl Document {
         }
     }
     /**Locks the iview */
-    pub(crate) fn stepback_tab(&mut self) -> (Option<Arc<Mutex<IView>>>, Option<Arc<Mutex<IView>>>) {
+    pub(crate) fn stepback_tab(&mut self) -> FocusChange {
         let prev_iview_lk = self.focused_element();
         // LOGLn!(
         //     "START: {} {} {}",
@@ -427,8 +1102,15 @@ impl Document {
         //     self.taborder.len(),
         //     self.next_tab_id
         // );
-        self.tabindex -= 1;
         self.next_tab_id = -1;
+        if let Some(members) = self.scope_members() {
+            let pos = members.iter().position(|&idx| idx == self.tabindex);
+            let len = members.len();
+            self.tabindex = members[pos.map_or(len - 1, |pos| (pos + len - 1) % len)];
+            return (prev_iview_lk, self.focused_element());
+        }
+        // going back from the first element leaves nothing focused, as going ahead from the last one does
+        self.tabindex = self.tabindex.wrapping_sub(1);
         if self.tabindex > self.taborder.len() {
             self.tabindex = self.taborder.len();
         }
@@ -438,7 +1120,7 @@ impl Document {
 
     /** Change the focus to given current next_tab_id if available
      */
-    pub(crate) fn focus(&mut self) -> (Option<Arc<Mutex<IView>>>, Option<Arc<Mutex<IView>>>) {
+    pub(crate) fn focus(&mut self) -> FocusChange {
         let prev_iview_lk = self.focused_element();
         if let Some(idx) = self
             .taborder
@@ -451,6 +1133,103 @@ impl Document {
         (prev_iview_lk, self.focused_element())
     }
 
+    /** Change the focus to the first element in tab order with the given key if available */
+    pub(crate) fn focus_key(&mut self, key: &str) -> FocusChange {
+        self.next_tab_id = self
+            .taborder
+            .iter()
+            .find(|ielement| ielement.key.as_deref() == Some(key))
+            .map_or(-1, |ielement| ielement.id);
+        self.focus()
+    }
+
+    /**
+     * Moves the focus to the next (or previous) element in the focus group of the focused element, wrapping around.
+     * None if the focused element is not in a focus group.
+     * Locks the iviews
+     */
+    pub(crate) fn move_in_group(&mut self, forward: bool) -> Option<FocusChange> {
+        let group = self.focused_element()?.lock().unwrap().style.focus_group.clone();
+        if group.is_empty() {
+            return None;
+        }
+        let members: Vec<usize> = (0..self.taborder.len())
+            .filter(|&idx| self.taborder[idx].iview.lock().unwrap().style.focus_group == group)
+            .collect();
+        let pos = members.iter().position(|&idx| idx == self.tabindex)?;
+        let next = if forward {
+            (pos + 1) % members.len()
+        } else {
+            (pos + members.len() - 1) % members.len()
+        };
+        self.next_tab_id = self.taborder[members[next]].id;
+        Some(self.focus())
+    }
+
+    /**
+     * returns the indices in taborder of the elements inside the last mounted FocusScope.
+     * None if there is no FocusScope or it has no focusable element
+     */
+    fn scope_members(&self) -> Option<Vec<usize>> {
+        let scope = self.focus_scopes.last()?.lock().unwrap();
+        let members: Vec<usize> = (0..self.taborder.len())
+            .filter(|&idx| scope.members.contains(&self.taborder[idx].id))
+            .collect();
+        if members.is_empty() { None } else { Some(members) }
+    }
+
+    /**
+     * Takes the FocusScopes found in the tree at this render.
+     * A newly mounted scope focuses its first element. When a scope is unmounted and nothing is focused,
+     * the element focused before it was mounted gets the focus back.
+     */
+    pub(crate) fn update_focus_scopes(&mut self, scopes: Vec<Arc<Mutex<FocusScopeState>>>) {
+        let contains = |list: &Vec<Arc<Mutex<FocusScopeState>>>, scope: &Arc<Mutex<FocusScopeState>>| {
+            list.iter().any(|element| Arc::ptr_eq(element, scope))
+        };
+        // innermost first, hence the outermost one decides the focus
+        let unmounted: Vec<_> = self
+            .focus_scopes
+            .iter()
+            .rev()
+            .filter(|scope| !contains(&scopes, scope))
+            .cloned()
+            .collect();
+        self.focus_scopes.retain(|scope| contains(&scopes, scope));
+        if self.tabindex >= self.taborder.len() {
+            unmounted.iter().for_each(|scope| {
+                let restore_id = scope.lock().unwrap().restore_id;
+                if restore_id >= 0 {
+                    self.focus_request = Some(FOCUSREQUEST::ID(restore_id));
+                }
+            });
+        }
+
+        // a pending focus (Eg: by the click which mounted the scope) counts as focused
+        let focused_id = match &self.focus_request {
+            Some(FOCUSREQUEST::ID(id)) => *id,
+            _ => self.taborder.get(self.tabindex).map_or(-1, |ielement| ielement.id),
+        };
+        scopes.into_iter().for_each(|scope_lk| {
+            if contains(&self.focus_scopes, &scope_lk) {
+                return;
+            }
+            {
+                let mut scope = scope_lk.lock().unwrap();
+                scope.restore_id = focused_id;
+                if let Some(first) = self.taborder.iter().find(|ielement| scope.members.contains(&ielement.id)) {
+                    self.focus_request = Some(FOCUSREQUEST::ID(first.id));
+                }
+            }
+            self.focus_scopes.push(scope_lk);
+        });
+    }
+
+    /** key of the focused element */
+    pub(crate) fn focused_key(&self) -> Option<String> {
+        self.taborder.get(self.tabindex)?.key.clone()
+    }
+
     pub(crate) fn update_focused_iview(&mut self, iview: Arc<Mutex<IView>>, id: i32) {
         if self.tabindex >= self.taborder.len() {
             return;
@@ -0,0 +1,196 @@
+/***
+ * Key bindings declared once and resolved before the default keyboard handling
+ */
+
+use std::sync::{Arc, Mutex};
+
+use ncurses::endwin;
+
+use crate::interfaces::{EVENT, Key, KeyEvent, Modifiers};
+
+/** ms to wait for the next key of a chord. After it, the keys typed so far run their own binding if any */
+pub(crate) const CHORD_TIMEOUT: u128 = 1000;
+
+#[derive(Clone)]
+pub(crate) struct Binding {
+    pub(crate) keys: Vec<KeyEvent>,
+    /** keys as given to bind. Shown in the help */
+    pub(crate) text: String,
+    pub(crate) description: String,
+    pub(crate) handler: Arc<Mutex<dyn FnMut(&mut EVENT) + 'static>>,
+}
+
+/**
+ * Set of key bindings. Attach to the app using `set_keymap` or to a subtree using `keymap` of `View` and `FocusScope`.
+ * Bindings of the elements on the path from the focused element to the root are active, the innermost one wins.
+ */
+#[derive(Clone, Default)]
+pub struct Keymap {
+    pub(crate) bindings: Vec<Binding>,
+}
+
+// handlers are only called from the main loop, same as the ones in Style
+unsafe impl Send for Keymap {}
+
+impl Keymap {
+    pub fn new() -> Keymap {
+        Keymap { bindings: vec![] }
+    }
+
+    /**
+     * Binds the keys to the handler.
+     * keys: space separated chords, each one is modifiers and a key joined by '+'. Eg: "ctrl+s", "g g", "?", "shift+tab", "f1".
+     * Modifiers: "ctrl", "alt", "shift". Keys: a character, "enter", "tab", "esc", "space", "backspace", "delete", "insert",
+     * "up", "down", "left", "right", "home", "end", "pageup", "pagedown", "f1".."f63".
+     * Panics if the keys are invalid, already bound in this Keymap or start one of its bindings, or the other way round. Eg: "g" and "g g".
+     */
+    pub fn bind<T: FnMut(&mut EVENT) + 'static>(mut self, keys: &str, description: &str, handler: T) -> Self {
+        let parsed = parse_keys(keys);
+        if let Some(binding) = self
+            .bindings
+            .iter()
+            .find(|binding| binding.keys.starts_with(&parsed) || parsed.starts_with(&binding.keys))
+        {
+            endwin();
+            panic!("Conflicting key binding: {} and {}", keys.trim(), binding.text);
+        }
+        self.bindings.push(Binding {
+            keys: parsed,
+            text: keys.trim().to_string(),
+            description: description.to_string(),
+            handler: Arc::new(Mutex::new(handler)),
+        });
+        self
+    }
+}
+
+/** Parses space separated chords. Eg: "ctrl+x ctrl+s" */
+pub(crate) fn parse_keys(keys: &str) -> Vec<KeyEvent> {
+    let parsed: Vec<KeyEvent> = keys.split_whitespace().map(parse_chord).collect();
+    if parsed.is_empty() {
+        endwin();
+        panic!("Invalid key binding: empty");
+    }
+    parsed
+}
+
+fn parse_chord(chord: &str) -> KeyEvent {
+    // "ctrl++" binds '+'
+    let (modifiers, key) = match chord.strip_suffix("++") {
+        Some(modifiers) => (modifiers, "+"),
+        None => chord.rsplit_once('+').unwrap_or(("", chord)),
+    };
+    let mut modifiers = modifiers
+        .split('+')
+        .filter(|m| !m.is_empty())
+        .fold(Modifiers::NONE, |prev, m| {
+            prev | match m.to_lowercase().as_str() {
+                "ctrl" => Modifiers::CTRL,
+                "alt" => Modifiers::ALT,
+                "shift" => Modifiers::SHIFT,
+                _ => {
+                    endwin();
+                    panic!("Invalid key binding modifier: {m}")
+                }
+            }
+        });
+
+    let mut chars = key.chars();
+    let key = match (chars.next(), chars.next()) {
+        (Some(c), None) => Key::Char(c),
+        _ => match key.to_lowercase().as_str() {
+            "enter" | "return" => Key::Enter,
+            "tab" => Key::Tab,
+            "esc" | "escape" => Key::Esc,
+            "space" => Key::Char(' '),
+            "backspace" => Key::Backspace,
+            "delete" | "del" => Key::Delete,
+            "insert" | "ins" => Key::Insert,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" | "pgup" => Key::PageUp,
+            "pagedown" | "pgdn" => Key::PageDown,
+            name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                Some(n @ 1..=63) => Key::F(n),
+                _ => {
+                    endwin();
+                    panic!("Invalid key binding key: {key}")
+                }
+            },
+        },
+    };
+
+    // same form as the decoded keys: Ctrl gives the lowercase letter, Shift is in the letter itself
+    let key = match key {
+        Key::Char(c) if modifiers.contains(Modifiers::CTRL) => Key::Char(c.to_ascii_lowercase()),
+        Key::Char(c) if modifiers.contains(Modifiers::SHIFT) => {
+            modifiers = Modifiers(modifiers.0 & !Modifiers::SHIFT.0);
+            Key::Char(c.to_ascii_uppercase())
+        }
+        key => key,
+    };
+    KeyEvent::new(key, modifiers)
+}
+
+/** whether some binding starts with the keys and is longer */
+pub(crate) fn is_prefix(keymaps: &[Keymap], keys: &[KeyEvent]) -> bool {
+    keymaps
+        .iter()
+        .flat_map(|keymap| keymap.bindings.iter())
+        .any(|binding| binding.keys.len() > keys.len() && binding.keys.starts_with(keys))
+}
+
+/**
+ * Bindings of an inner keymap which hide the ones of an outer keymap or share a prefix with them.
+ * returns a message for each
+ */
+pub(crate) fn conflicts(keymaps: &[Keymap]) -> Vec<String> {
+    let mut messages = vec![];
+    for (i, inner) in keymaps.iter().enumerate() {
+        for outer in &keymaps[i + 1..] {
+            for a in &inner.bindings {
+                for b in &outer.bindings {
+                    if a.keys == b.keys {
+                        messages.push(format!("Key binding {} hides the one of an outer keymap", a.text));
+                    } else if a.keys.starts_with(&b.keys) || b.keys.starts_with(&a.keys) {
+                        messages.push(format!(
+                            "Key bindings {} and {} of an outer keymap share a prefix. The shorter one waits for the chord timeout",
+                            a.text, b.text
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    messages
+}
+
+/** Binding for exactly these keys, from the innermost keymap */
+pub(crate) fn find_binding(keymaps: &[Keymap], keys: &[KeyEvent]) -> Option<Binding> {
+    keymaps
+        .iter()
+        .flat_map(|keymap| keymap.bindings.iter())
+        .find(|binding| binding.keys == keys)
+        .cloned()
+}
+
+/** (keys, description) of the bindings, leaving out the ones hidden by an inner keymap */
+pub(crate) fn list_bindings(keymaps: &[Keymap]) -> Vec<(String, String)> {
+    let mut seen: Vec<&Vec<KeyEvent>> = vec![];
+    keymaps
+        .iter()
+        .flat_map(|keymap| keymap.bindings.iter())
+        .filter(|binding| {
+            if seen.contains(&&binding.keys) {
+                return false;
+            }
+            seen.push(&binding.keys);
+            true
+        })
+        .map(|binding| (binding.text.clone(), binding.description.clone()))
+        .collect()
+}
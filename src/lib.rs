@@ -11,26 +11,39 @@ TODO:
 use dyn_clone::clone;
 use interfaces::{Component, Fiber, IViewContent, Stateful};
 use ncurses::{
-    cbreak, curs_set, endwin, getch, getmaxyx, getmouse, has_colors, initscr, keypad, mmask_t, mouseinterval, mousemask, nodelay, noecho, refresh, start_color, stdscr, use_default_colors, wrefresh, ALL_MOUSE_EVENTS, BUTTON1_PRESSED, BUTTON2_PRESSED, COLOR_PAIRS, KEY_BTAB, KEY_DOWN, KEY_ENTER, KEY_LEFT, KEY_MOUSE, KEY_RESIZE, KEY_RIGHT, KEY_UP, MEVENT, OK
+    cbreak, clearok, ungetch, curs_set, set_escdelay, setlocale, LcCategory, endwin, getch, getmaxyx, getmouse, has_colors, initscr, keypad, mmask_t, mouseinterval, mousemask, nodelay, noecho, refresh, reset_prog_mode, start_color, stdscr, timeout, use_default_colors, wrefresh, ALL_MOUSE_EVENTS, BUTTON1_DOUBLE_CLICKED, BUTTON1_PRESSED, BUTTON1_RELEASED, BUTTON2_PRESSED, COLOR_PAIRS, COLORS, KEY_BTAB, KEY_DOWN, KEY_END, KEY_ENTER, KEY_HOME, KEY_LEFT, KEY_NPAGE, KEY_PPAGE, KEY_MOUSE, KEY_RESIZE, KEY_RIGHT, KEY_UP, MEVENT, OK, REPORT_MOUSE_POSITION
 };
 use nmodels::iview::IView;
+use nmodels::keys::decode_key;
 use std::{
     any::TypeId,
     collections::HashMap,
+    ffi::{CString, c_char, c_int},
     fmt::Debug,
-    i32, panic,
+    i32,
+    io::Write,
+    mem::{replace, take},
+    panic,
     sync::{Arc, LazyLock, Mutex},
+    time::Instant,
 };
 
 use crate::interfaces::Document;
-use crate::interfaces::{BASICSTRUCT, EVENT};
+use crate::interfaces::{BASICSTRUCT, EVENT, ElementRef, FOCUSREQUEST};
 use crate::styles::DIMEN;
-use crate::styles::STYLE;
+use crate::styles::{Handler, STYLE, Style};
+use crate::styles::{KEY_CTRL_END, KEY_CTRL_HOME, KEY_PASTE_END, KEY_PASTE_START};
+use crate::keymap::Keymap;
+use crate::signals::{SIGNAL, install_handlers, stop, take_pending};
+use crate::theme::Theme;
 
 pub mod components;
 pub mod interfaces;
+pub mod keymap;
 mod nmodels;
+pub mod signals;
 pub mod styles;
+pub mod theme;
 
 #[macro_export]
 macro_rules! LOGLn {
@@ -73,12 +86,40 @@ fn get_key(v: &Arc<Mutex<dyn Component>>) -> String {
         .map_or(format!(""), |f| f.clone())
 }
 
+unsafe extern "C" {
+    /** ncurses function, not exposed by the ncurses crate */
+    #[link_name = "define_key"]
+    fn ncurses_define_key(definition: *const c_char, keycode: c_int) -> c_int;
+}
+
+/** Makes getch return the keycode for the escape sequence. Replaces any previous binding of the sequence */
+fn define_key(definition: &str, keycode: i32) {
+    let Ok(definition) = CString::new(definition) else {
+        return;
+    };
+    unsafe {
+        ncurses_define_key(definition.as_ptr(), keycode);
+    }
+}
+
+/** ms to wait after Esc for the rest of an escape sequence. ncurses waits a second by default */
+const ESC_DELAY: i32 = 25;
+/** ms to wait for the rest of a paste. After it, the text read so far is taken as the paste */
+const PASTE_TIMEOUT: i32 = 1000;
+/** ms within which a click at the same place counts as the next click of a double click */
+const DOUBLE_CLICK_TIME: u128 = 400;
+
 /**
  * Initalize the window
  * uses DOCUMENT.lock()
  */
 fn initialize() {
+    // without the locale ncurses treats each byte of a multibyte character as a character
+    let _ = setlocale(LcCategory::all, "");
     initscr();
+    let (mut rows, mut columns) = (0, 0);
+    getmaxyx(stdscr(), &mut rows, &mut columns);
+    DOCUMENT.lock().unwrap().resize_terminal((rows, columns));
     noecho();
     keypad(stdscr(), true);
     curs_set(ncurses::CURSOR_VISIBILITY::CURSOR_INVISIBLE);
@@ -86,13 +127,22 @@ fn initialize() {
     cbreak();
     nodelay(stdscr(), true); // make getch non-blocking
     use_default_colors();
-    mousemask((ALL_MOUSE_EVENTS) as mmask_t, None);
+    mousemask((ALL_MOUSE_EVENTS | REPORT_MOUSE_POSITION) as mmask_t, None);
+    set_terminal_modes();
     mouseinterval(0);
+    // xterm's sequences for Ctrl + Home / End. ncurses does not give them a fixed code
+    define_key("\x1b[1;5H", KEY_CTRL_HOME);
+    define_key("\x1b[1;5F", KEY_CTRL_END);
+    define_key("\x1b[200~", KEY_PASTE_START);
+    define_key("\x1b[201~", KEY_PASTE_END);
+    // Esc followed by a key within this many ms is read as Alt + key
+    set_escdelay(ESC_DELAY);
     if has_colors() {
         start_color();
         {
             let mut document = DOCUMENT.lock().unwrap();
             document.total_allowed_pairs = COLOR_PAIRS();
+            document.max_supported_colors = COLORS();
         }
         use_default_colors();
         // DOCUMENT.lock().unwrap().has_color = true;
@@ -197,6 +247,17 @@ fn create_tree(
     call_n_create_with_fiber(node, currfiber_lk.clone(), parent, changed)
 }
 
+/** IView holding the app. Its colors are the theme's, which the elements without their own inherit */
+fn theme_root() -> Arc<Mutex<IView>> {
+    IView::new()
+        .set_style(STYLE::HIEGHT(DIMEN::PERCENT(100.)))
+        .set_style(STYLE::WIDTH(DIMEN::PERCENT(100.)))
+        .set_style(STYLE::BACKGROUNDCOLOR(theme::BACKGROUND))
+        .set_style(STYLE::TEXTCOLOR(theme::ON_BACKGROUND))
+        .set_style(STYLE::BORDERCOLOR(theme::BORDER))
+        .build()
+}
+
 /**
  * Returns the root IView
  * Root Fiber will be in CURRFIBER
@@ -204,13 +265,7 @@ fn create_tree(
  * **HAS** SIDE-EFFECTS
  */
 fn create_render_tree(node: Arc<Mutex<dyn Component>>) -> Result<Arc<Mutex<IView>>, String> {
-    let parent = IView::new()
-        .set_style(STYLE::HIEGHT(DIMEN::PERCENT(100.)))
-        .set_style(STYLE::WIDTH(DIMEN::PERCENT(100.)))
-        .set_style(STYLE::BACKGROUNDCOLOR(-1))
-        .set_style(STYLE::TEXTCOLOR(-1))
-        .set_style(STYLE::BORDERCOLOR(-1))
-        .build();
+    let parent = theme_root();
 
     let fiber = create_tree(node, parent.clone(), true)?;
 
@@ -559,6 +614,7 @@ fn check_for_change(
                 if document.is_active(prev_iview) {
                     document.set_active(iview.clone());
                 }
+                document.replace_in_mouse_paths(prev_iview, &iview);
                 let _ = document.find_n_update(prev_iview.lock().unwrap().id, &iview);
 
                 REMOVEINDEX
@@ -647,12 +703,24 @@ fn tree_refresh(root: Arc<Mutex<IView>>) -> Result<(i32, i32, bool), String> {
     let y = &mut 0;
     refresh();
     getmaxyx(stdscr(), y, x);
-    {
+    let scroll_requests = {
         let mut document = DOCUMENT.lock().unwrap();
         document.clear_color_pairs();
         // document._clear_tab_order();
+        take(&mut document.scroll_requests)
+    };
+    // scroll requests are applied by the IView during its layout
+    scroll_requests
+        .iter()
+        .filter_map(|iview| iview.upgrade())
+        .for_each(|iview| iview.lock().unwrap().style.render = true);
+    let res = {
+        let mut root = root.lock().unwrap();
+        if root.style.render {
+            root.resolve_pseudo_style();
+        }
+        root.__init__(*y, *x, -1, -1, -1, 0)?
     };
-    let res = root.lock().unwrap().__init__(*y, *x, -1, -1, -1)?;
     if res.2 {
         {
             let mut document = DOCUMENT.lock().unwrap();
@@ -665,6 +733,16 @@ fn tree_refresh(root: Arc<Mutex<IView>>) -> Result<(i32, i32, bool), String> {
         }
 
         let _ = root.lock().unwrap().__render__()?;
+        let generation = {
+            let mut document = DOCUMENT.lock().unwrap();
+            document.refresh_count += 1;
+            document.refresh_count
+        };
+        root.lock().unwrap().update_refs((0, 0), generation);
+        let mut scopes = vec![];
+        root.lock().unwrap().collect_focus_scopes(&mut vec![], &mut scopes);
+        DOCUMENT.lock().unwrap().update_focus_scopes(scopes);
+        update_active_keymaps();
         let Some(basic_struct) = &root.lock().unwrap().basic_struct else {
             panic!("NO window at root");
         };
@@ -675,23 +753,49 @@ fn tree_refresh(root: Arc<Mutex<IView>>) -> Result<(i32, i32, bool), String> {
         refresh();
     }
     DOCUMENT.lock().unwrap().changed = false;
+    if res.2 {
+        report_resized(root);
+    }
     Ok(res)
 }
 
 /**
- * Bubbles up from current active to the parent
+ * Calls onresize of the elements whose size changed in the last layout.
+ * locks the iviews
  */
-fn handle_event(iview_lk: Arc<Mutex<IView>>, event: &mut EVENT) {
-    let iview = iview_lk.lock().unwrap();
-
-    iview.style.handle_event(event, false);
-
-    let Some(parent) = iview.parent.clone() else {
-        return;
-    };
+fn report_resized(root: Arc<Mutex<IView>>) {
+    let mut resized = vec![];
+    root.lock().unwrap().collect_resized(&mut resized);
+    resized.into_iter().for_each(|(iview_lk, (height, width))| {
+        let iview = iview_lk.lock().unwrap();
+        let mut event = EVENT::new(KEY_RESIZE);
+        event.height = height;
+        event.width = width;
+        if let Some(onresize) = iview.style.onresize.clone() {
+            onresize.lock().unwrap()(&mut event);
+        }
+    });
+}
 
-    if event.propogate {
-        handle_event(parent, event);
+/**
+ * Dispatches the keyboard or paste event along the path from the root to the target.
+ * Capture handlers are called from the root down to the target, then bubble handlers from the target up to the root.
+ * Locks one IView at a time.
+ */
+fn dispatch_key_event(target: Arc<Mutex<IView>>, event: &mut EVENT) {
+    let mut path = vec![];
+    let mut next = Some(target);
+    while let Some(iview_lk) = next {
+        next = iview_lk.lock().unwrap().parent.clone();
+        path.push(iview_lk);
+    }
+    let capture = path.iter().rev().map(|iview| (iview, true));
+    let bubble = path.iter().map(|iview| (iview, false));
+    for (iview_lk, capture) in capture.chain(bubble) {
+        if !event.propogate {
+            return;
+        }
+        iview_lk.lock().unwrap().style.handle_event(event, capture);
     }
 }
 
@@ -713,9 +817,11 @@ pub(crate) fn handle_focus_change(
         return Ok(());
     }
     let mut e = EVENT::new(0);
+    let mut repaint = false;
     if let Some(iview_lk) = prev_iview {
         let mut iview = iview_lk.lock().unwrap();
         iview.focused = false;
+        repaint |= !iview.style.focus_style.is_empty();
         if let Some(onunfocus) = iview.style.onunfocus.clone() {
             onunfocus.lock().unwrap()(&mut e);
         }
@@ -723,6 +829,7 @@ pub(crate) fn handle_focus_change(
     if let Some(iview_lk) = new_iview {
         let mut iview = iview_lk.lock().unwrap();
         iview.focused = true;
+        repaint |= !iview.style.focus_style.is_empty();
         if let Some(onfocus) = iview.style.onfocus.clone() {
             onfocus.lock().unwrap()(&mut e);
         }
@@ -730,21 +837,159 @@ pub(crate) fn handle_focus_change(
             DOCUMENT.lock().unwrap().set_active(iview_lk.clone());
         }
     }
+    if repaint {
+        DOCUMENT.lock().unwrap().changed = true;
+    }
+    update_active_keymaps();
     Ok(())
 }
 
 /**
- * returns whether to exit the program
+ * Moves the focus as asked through the public api, if asked
+ * Uses DOCUMENT.lock()
  */
-fn handle_keyboard_event(ch: i32) -> Result<bool, String> {
-    let focused_iview = {
-        let document = DOCUMENT.lock().unwrap();
-        let iview = document.focused_element();
+fn apply_focus_request() -> Result<(), String> {
+    let (prev_iview, new_iview) = {
+        let mut document = DOCUMENT.lock().unwrap();
+        let Some(request) = document.focus_request.take() else {
+            return Ok(());
+        };
+        match request {
+            FOCUSREQUEST::ID(id) => {
+                document.next_tab_id = id;
+                document.focus()
+            }
+            FOCUSREQUEST::BLUR(id) => {
+                let prev_iview = document.focused_element();
+                if document.tabindex < document.taborder.len()
+                    && document.taborder[document.tabindex].id == id
+                {
+                    document.tabindex = document.taborder.len();
+                }
+                (prev_iview, document.focused_element())
+            }
+            FOCUSREQUEST::NEXT => document.advance_tab(),
+            FOCUSREQUEST::PREV => document.stepback_tab(),
+            FOCUSREQUEST::KEY(key) => document.focus_key(&key),
+        }
+    };
+    handle_focus_change(prev_iview, new_iview)
+}
+
+/**
+ * returns the IView keyboard events go to: the focused one, else the top IView of the app
+ * Uses DOCUMENT.lock()
+ */
+fn key_target() -> Option<Arc<Mutex<IView>>> {
+    let document = DOCUMENT.lock().unwrap();
+    document.focused_element().or_else(|| {
+        let fiber = document.curr_fiber.as_ref()?;
+        let iview = fiber.lock().unwrap().iview.clone();
         iview
+    })
+}
+
+/**
+ * Collects the keymaps on the path from the key target to the root, followed by the app's one.
+ * Called whenever the tree or the focus changes. Locks one IView at a time
+ */
+fn update_active_keymaps() {
+    let mut keymaps = vec![];
+    let mut next = key_target();
+    while let Some(iview_lk) = next {
+        let iview = iview_lk.lock().unwrap();
+        keymaps.extend(iview.keymap.clone());
+        next = iview.parent.clone();
+    }
+    let mut document = DOCUMENT.lock().unwrap();
+    keymaps.extend(document.keymap.clone());
+    let conflicts = keymap::conflicts(&keymaps);
+    for conflict in &conflicts {
+        if !document.keymap_conflicts.contains(conflict) {
+            LOGLn!("WARNING: {}", conflict);
+        }
+    }
+    document.keymap_conflicts = conflicts;
+    document.active_keymaps = keymaps;
+}
+
+/**
+ * Resolves the key against the active keymaps, along with the keys of a pending chord.
+ * returns whether the key was taken by a binding or a chord.
+ */
+fn handle_keymaps(event: &mut EVENT) -> bool {
+    let Some(key) = event.key_event else {
+        return false;
+    };
+    let (keymaps, pending) = {
+        let mut document = DOCUMENT.lock().unwrap();
+        document.pending_since = None;
+        (document.active_keymaps.clone(), take(&mut document.pending_keys))
     };
+    if keymaps.is_empty() {
+        return false;
+    }
+    let mut keys = pending.clone();
+    keys.push(key);
+    if !pending.is_empty() && !keymap::is_prefix(&keymaps, &keys) && keymap::find_binding(&keymaps, &keys).is_none() {
+        // chord is broken. Keys typed so far run their own binding, then this key is tried alone
+        if let Some(binding) = keymap::find_binding(&keymaps, &pending) {
+            binding.handler.lock().unwrap()(event);
+        }
+        keys = vec![key];
+    }
+    if keymap::is_prefix(&keymaps, &keys) {
+        let mut document = DOCUMENT.lock().unwrap();
+        document.pending_keys = keys;
+        document.pending_since = Some(Instant::now());
+        return true;
+    }
+    match keymap::find_binding(&keymaps, &keys) {
+        Some(binding) => {
+            binding.handler.lock().unwrap()(event);
+            true
+        }
+        None => false,
+    }
+}
+
+/**
+ * Runs the binding of the pending chord's keys once no key came for CHORD_TIMEOUT.
+ * Uses DOCUMENT.lock()
+ */
+fn check_chord_timeout() {
+    let (keymaps, keys) = {
+        let mut document = DOCUMENT.lock().unwrap();
+        match document.pending_since {
+            Some(since) if since.elapsed().as_millis() >= keymap::CHORD_TIMEOUT => {
+                document.pending_since = None;
+                (document.active_keymaps.clone(), take(&mut document.pending_keys))
+            }
+            _ => return,
+        }
+    };
+    if let Some(binding) = keymap::find_binding(&keymaps, &keys) {
+        let mut event = EVENT::new(-1);
+        event.key_event = keys.last().copied();
+        binding.handler.lock().unwrap()(&mut event);
+    }
+}
+
+/**
+ * returns whether to exit the program
+ */
+fn handle_keyboard_event(ch: i32) -> Result<bool, String> {
+    let target = key_target();
+    let key_event = decode_key(ch, &mut getch, &mut |ch| {
+        ungetch(ch);
+    });
     let mut event = EVENT::new(ch);
-    if let Some(iview) = focused_iview.clone() {
-        handle_event(iview, &mut event);
+    event.key_event = Some(key_event);
+    if let Some(iview) = target {
+        dispatch_key_event(iview, &mut event);
+    }
+    if event.default && handle_keymaps(&mut event) {
+        return Ok(false);
     }
     // handle regular functionality if default is on
     const TAB: i32 = '\t' as i32;
@@ -767,7 +1012,17 @@ fn handle_keyboard_event(ch: i32) -> Result<bool, String> {
                 };
                 handle_focus_change(prev_iview, new_iview)?;
             }
-            KEY_UP | KEY_DOWN | KEY_RIGHT | KEY_LEFT => {
+            KEY_UP | KEY_DOWN | KEY_RIGHT | KEY_LEFT
+                if let Some((prev_iview, new_iview)) = {
+                    let mut document = DOCUMENT.lock().unwrap();
+                    document.move_in_group(matches!(ch, KEY_DOWN | KEY_RIGHT))
+                } =>
+            {
+                // moves within the focus group of the focused element
+                handle_focus_change(prev_iview, new_iview)?;
+            }
+            KEY_UP | KEY_DOWN | KEY_RIGHT | KEY_LEFT | KEY_PPAGE | KEY_NPAGE | KEY_HOME
+            | KEY_END | KEY_CTRL_HOME | KEY_CTRL_END => {
                 // scroll on current active element
                 if let Some(iview) = {
                     let document = DOCUMENT.lock().unwrap();
@@ -786,6 +1041,193 @@ fn handle_keyboard_event(ch: i32) -> Result<bool, String> {
     Ok(false)
 }
 
+/**
+ * Updates hovered and pressed state of the IViews using the path found under the last mouse event.
+ * Marks the document changed only if some IView having a pseudo-class style for that state changed.
+ */
+fn update_mouse_states(mevent: MEVENT) {
+    let (left, entered) = update_pressed_n_hovered(mevent.bstate);
+
+    // leaving goes from the innermost element and entering from the outermost one
+    let mut event = EVENT::new(KEY_MOUSE);
+    event.mevent = Some(mevent);
+    event.clientx = mevent.x;
+    event.clienty = mevent.y;
+    left.iter().rev().for_each(|iview_lk| {
+        let iview = iview_lk.lock().unwrap();
+        if let Some(onmouseleave) = iview.style.onmouseleave.clone() {
+            onmouseleave.lock().unwrap()(&mut event);
+        }
+    });
+    entered.iter().for_each(|iview_lk| {
+        let iview = iview_lk.lock().unwrap();
+        if let Some(onmouseenter) = iview.style.onmouseenter.clone() {
+            onmouseenter.lock().unwrap()(&mut event);
+        }
+    });
+}
+
+/** (left, entered) IViews */
+type HoverChange = (Vec<Arc<Mutex<IView>>>, Vec<Arc<Mutex<IView>>>);
+
+/**
+ * Sets the hovered and pressed flags and makes the mouse path the hover path.
+ * returns the IViews the mouse left and the ones it entered, outermost first.
+ * Uses DOCUMENT.lock(), locks the iviews
+ */
+fn update_pressed_n_hovered(bstate: mmask_t) -> HoverChange {
+    let mut document = DOCUMENT.lock().unwrap();
+    let path = take(&mut document.mouse_path);
+    let contains = |list: &Vec<Arc<Mutex<IView>>>, iview: &Arc<Mutex<IView>>| {
+        list.iter().any(|element| Arc::ptr_eq(element, iview))
+    };
+    let mut changed = false;
+
+    let left: Vec<Arc<Mutex<IView>>> = document
+        .hover_path
+        .iter()
+        .filter(|iview| !contains(&path, iview))
+        .cloned()
+        .collect();
+    left.iter().for_each(|iview_lk| {
+        let mut iview = iview_lk.lock().unwrap();
+        iview.hovered = false;
+        changed |= !iview.style.hover_style.is_empty();
+    });
+    let entered: Vec<Arc<Mutex<IView>>> = path
+        .iter()
+        .filter(|iview| !contains(&document.hover_path, iview))
+        .cloned()
+        .collect();
+    entered.iter().for_each(|iview_lk| {
+        let mut iview = iview_lk.lock().unwrap();
+        iview.hovered = true;
+        changed |= !iview.style.hover_style.is_empty();
+    });
+
+    if bstate & BUTTON1_PRESSED as mmask_t > 0 {
+        path.iter().for_each(|iview_lk| {
+            let mut iview = iview_lk.lock().unwrap();
+            iview.pressed = true;
+            changed |= !iview.style.active_style.is_empty();
+        });
+        document.pressed_path = path.clone();
+    } else if bstate & BUTTON1_RELEASED as mmask_t > 0 {
+        take(&mut document.pressed_path).iter().for_each(|iview_lk| {
+            let mut iview = iview_lk.lock().unwrap();
+            iview.pressed = false;
+            changed |= !iview.style.active_style.is_empty();
+        });
+    }
+
+    document.hover_path = path;
+    document.changed |= changed;
+    (left, entered)
+}
+
+/**
+ * Reads the pasted text up to the end marker of bracketed paste.
+ * Keys which ncurses decoded from escape sequences inside the text are dropped
+ */
+fn read_paste() -> String {
+    let mut bytes = vec![];
+    // getch waits for the next byte instead of returning at once
+    timeout(PASTE_TIMEOUT);
+    loop {
+        match getch() {
+            KEY_PASTE_END | -1 => break,
+            ch @ 0..=255 => bytes.push(ch as u8),
+            _ => {}
+        }
+    }
+    nodelay(stdscr(), true);
+    // terminals send the line breaks as carriage returns
+    String::from_utf8_lossy(&bytes).replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/**
+ * Counts the left clicks made in a row at the same place, each within DOUBLE_CLICK_TIME of the previous one.
+ * returns 0 for other mouse events
+ * Uses DOCUMENT.lock()
+ */
+fn count_clicks(mevent: &MEVENT) -> i32 {
+    if mevent.bstate & BUTTON1_DOUBLE_CLICKED as mmask_t > 0 {
+        return 2;
+    }
+    if mevent.bstate & BUTTON1_PRESSED as mmask_t == 0 {
+        return 0;
+    }
+    let mut document = DOCUMENT.lock().unwrap();
+    let count = match document.last_click {
+        Some((time, position, count))
+            if position == (mevent.y, mevent.x) && time.elapsed().as_millis() <= DOUBLE_CLICK_TIME =>
+        {
+            count + 1
+        }
+        _ => 1,
+    };
+    document.last_click = Some((Instant::now(), (mevent.y, mevent.x), count));
+    count
+}
+
+/**
+ * Calls ondragstart, ondrag and ondragend of the elements the left button was pressed on, innermost first.
+ * Mouse movement while the button is held is a drag. The release after it ends the drag.
+ * Uses DOCUMENT.lock(), locks the iviews
+ */
+fn handle_drag(mevent: MEVENT) {
+    let (path, start, started) = {
+        let mut document = DOCUMENT.lock().unwrap();
+        if mevent.bstate & BUTTON1_PRESSED as mmask_t > 0 {
+            document.press_start = Some((mevent.y, mevent.x));
+            document.dragging = false;
+            return;
+        }
+        let Some(start) = document.press_start else {
+            return;
+        };
+        let started = if mevent.bstate & BUTTON1_RELEASED as mmask_t > 0 {
+            document.press_start = None;
+            if !take(&mut document.dragging) {
+                return;
+            }
+            false
+        } else if mevent.bstate & REPORT_MOUSE_POSITION as mmask_t > 0 {
+            !replace(&mut document.dragging, true)
+        } else {
+            return;
+        };
+        (document.pressed_path.clone(), start, started)
+    };
+
+    let mut event = EVENT::new(KEY_MOUSE);
+    event.mevent = Some(mevent);
+    event.clientx = mevent.x;
+    event.clienty = mevent.y;
+    event.starty = start.0;
+    event.startx = start.1;
+    let mut bubble = |pick: fn(&Style) -> &Handler| {
+        event.propogate = true;
+        for iview_lk in path.iter().rev() {
+            if !event.propogate {
+                break;
+            }
+            let iview = iview_lk.lock().unwrap();
+            if let Some(handler) = pick(&iview.style).clone() {
+                handler.lock().unwrap()(&mut event);
+            }
+        }
+    };
+    if mevent.bstate & BUTTON1_RELEASED as mmask_t > 0 {
+        bubble(|style| &style.ondragend);
+        return;
+    }
+    if started {
+        bubble(|style| &style.ondragstart);
+    }
+    bubble(|style| &style.ondrag);
+}
+
 /**
  * returns true if to exit the app
  */
@@ -793,8 +1235,15 @@ fn handle_events(root: Arc<Mutex<IView>>) -> Result<bool, String> {
     let ch = getch();
     match ch {
         KEY_RESIZE => {
+            // marks the tree changed. Components using the terminal size are rendered again before the layout
             initialize();
-            tree_refresh(root.clone())?;
+        }
+        KEY_PASTE_START => {
+            let mut event = EVENT::new(ch);
+            event.paste = Some(read_paste());
+            if let Some(iview) = key_target() {
+                dispatch_key_event(iview, &mut event);
+            }
         }
         KEY_MOUSE => {
             let mut mevent = MEVENT {
@@ -806,18 +1255,40 @@ fn handle_events(root: Arc<Mutex<IView>>) -> Result<bool, String> {
             };
 
             if getmouse(&mut mevent) == OK {
+                // while dragging a scrollbar thumb the movement goes to its IView only
+                let drag = DOCUMENT.lock().unwrap().scroll_drag.clone();
+                if let Some(drag) = drag {
+                    if mevent.bstate & BUTTON1_RELEASED as mmask_t > 0 {
+                        DOCUMENT.lock().unwrap().scroll_drag = None;
+                    } else if mevent.bstate & REPORT_MOUSE_POSITION as mmask_t > 0 {
+                        drag.iview
+                            .lock()
+                            .unwrap()
+                            .drag_scrollbar(&drag, (mevent.y, mevent.x));
+                        return Ok(false);
+                    }
+                }
+                handle_drag(mevent);
                 let mut event = EVENT::new(ch);
                 event.mevent = Some(mevent);
                 event.clientx = mevent.x;
                 event.clienty = mevent.y;
+                event.click_count = count_clicks(&mevent);
                 if mevent.bstate & BUTTON1_PRESSED as u32 > 0
                     || mevent.bstate & BUTTON2_PRESSED as u32 > 0
                 {
                     // if button clicked the active will be set by `__handle_mouse_event__`
                     DOCUMENT.lock().unwrap().clear_active();
                 }
+                DOCUMENT.lock().unwrap().mouse_path.clear();
 
+                // the target is found before any handler runs, so that capture handlers see it too
+                event.locating = true;
+                root.lock().unwrap().__handle_mouse_event__(&mut event)?;
+                event.locating = false;
                 root.lock().unwrap().__handle_mouse_event__(&mut event)?;
+
+                update_mouse_states(mevent);
             }
         }
         val => {
@@ -826,14 +1297,82 @@ fn handle_events(root: Arc<Mutex<IView>>) -> Result<bool, String> {
                 if handle_keyboard_event(val)? {
                     return Ok(true);
                 }
+            } else {
+                check_chord_timeout();
             }
         }
     }
     return Ok(false);
 }
 
+/**
+ * Gives the signals received since the last call to the app's on_signal handler, then does the default for the ones it did not handle.
+ * returns true if to exit the app
+ */
+fn handle_signals() -> bool {
+    let mut exit = false;
+    for received in take_pending() {
+        let handled = SIGNAL_HANDLER
+            .lock()
+            .unwrap()
+            .as_mut()
+            .is_some_and(|handler| handler(received));
+        if handled {
+            continue;
+        }
+        match received {
+            SIGNAL::SUSPEND => {
+                restore_terminal();
+                stop();
+            }
+            SIGNAL::RESUME => {
+                reset_prog_mode();
+                refresh();
+                set_terminal_modes();
+                // the size may have changed while stopped. Marks the tree changed, the screen is cleared and drawn again on the next refresh
+                let (mut rows, mut columns) = (0, 0);
+                getmaxyx(stdscr(), &mut rows, &mut columns);
+                DOCUMENT.lock().unwrap().resize_terminal((rows, columns));
+                clearok(stdscr(), true);
+            }
+            SIGNAL::INTERRUPT | SIGNAL::TERMINATE => exit = true,
+        }
+    }
+    exit
+}
+
+/** Asks the terminal to report mouse movement and to bracket pastes. Undone by restore_terminal */
+fn set_terminal_modes() {
+    // most terminals need to be asked explicitly to report mouse movement (used by hover)
+    print!("\x1b[?1003h");
+    // pasted text comes between two markers instead of as typed keys
+    print!("\x1b[?2004h");
+    let _ = std::io::stdout().flush();
+}
+
+/** Calls the on_exit handlers, once. Skipped if the handlers are locked, as when one of them panics */
+fn run_exit_handlers() {
+    let handlers = match EXIT_HANDLERS.try_lock() {
+        Ok(mut handlers) => take(&mut *handlers),
+        Err(_) => return,
+    };
+    handlers.into_iter().for_each(|mut handler| handler());
+}
+
+/** Leaves curses mode and turns off the terminal modes set by initialize */
+fn restore_terminal() {
+    endwin();
+    // stop reporting mouse movement and bracketing pastes
+    print!("\x1b[?1003l");
+    print!("\x1b[?2004l");
+    let _ = std::io::stdout().flush();
+}
+
 fn run_app(root: Arc<Mutex<IView>>) -> Result<(), String> {
     loop {
+        if handle_signals() {
+            break;
+        }
         // if change, get the tree from the app.
         // diff the tree to get the changed components
         let mut changed = diff_n_update(root.clone())?;
@@ -852,6 +1391,7 @@ fn run_app(root: Arc<Mutex<IView>>) -> Result<(), String> {
             //     _debug_fiber_tree(fiber.clone(), 0);
             // }
         }
+        apply_focus_request()?;
         // return Err("()".to_string());
         // handle click and scroll
         if handle_events(root.clone())? {
@@ -863,6 +1403,15 @@ fn run_app(root: Arc<Mutex<IView>>) -> Result<(), String> {
 
 pub(crate) static REMOVEINDEX: Mutex<Vec<Arc<Mutex<IView>>>> = Mutex::new(vec![]);
 
+/** Called once the app exits. Set by on_exit */
+static EXIT_HANDLERS: Mutex<Vec<Box<dyn FnMut() + Send>>> = Mutex::new(vec![]);
+
+/** returns true if it handled the signal */
+type SignalHandler = Box<dyn FnMut(SIGNAL) -> bool + Send>;
+
+/** Set by on_signal */
+static SIGNAL_HANDLER: Mutex<Option<SignalHandler>> = Mutex::new(None);
+
 /************  Public Functions  ********** */
 
 pub static DOCUMENT: Mutex<Document> = Mutex::new(Document {
@@ -873,9 +1422,32 @@ pub static DOCUMENT: Mutex<Document> = Mutex::new(Document {
     next_tab_id: -1,
     changed: true,
     curr_active: None,
+    mouse_path: vec![],
+    hover_path: vec![],
+    pressed_path: vec![],
+    scroll_drag: None,
+    press_start: None,
+    dragging: false,
+    last_click: None,
+    scroll_requests: vec![],
+    focus_request: None,
+    refresh_count: 0,
+    keymap: None,
+    active_keymaps: vec![],
+    keymap_conflicts: vec![],
+    pending_keys: vec![],
+    pending_since: None,
+    focus_scopes: vec![],
+    terminal_size: (0, 0),
+    size_subscribers: vec![],
     color_pairs: LazyLock::new(|| Mutex::new(HashMap::<(i16, i16), u16>::new())),
+    colors: LazyLock::new(|| Mutex::new(HashMap::<(i16, i16, i16), u16>::new())),
+    custom_colors: vec![],
     total_allowed_pairs: 0,
+    max_supported_colors: 0,
+    curr_color: 0,
     curr_color_pair: 0,
+    theme: Theme::terminal(),
 });
 
 /**
@@ -940,6 +1512,121 @@ pub fn use_state<T: Stateful + Debug>(init_val: T) -> (T, Arc<dyn Fn(T) + Send +
     return (clone(downcasted_val), Arc::new(set_value));
 }
 
+/**
+ * returns (rows, columns) of the terminal.
+ * The Component is rendered again whenever the terminal is resized.
+ * Uses DOCUMENT lock()
+ */
+pub fn use_terminal_size() -> (i32, i32) {
+    let mut document = DOCUMENT.lock().unwrap();
+    let Some(fiber_lk) = document.curr_fiber.clone() else {
+        panic!("TERMINAL SIZE: No fiber associated with the component")
+    };
+    let fiber = Arc::downgrade(&fiber_lk);
+    if !document.size_subscribers.iter().any(|other| other.ptr_eq(&fiber)) {
+        document.size_subscribers.push(fiber);
+    }
+    document.terminal_size
+}
+
+/**
+ * returns a ElementRef which stays the same across the renders of this Component.
+ * Attach it to a base Component using `with_ref`.
+ */
+pub fn use_element_ref() -> ElementRef {
+    use_state(ElementRef::new()).0
+}
+
+/**
+ * Moves the focus to the next element in tab order, same as Tab.
+ * Applied after the current event. Uses DOCUMENT lock()
+ */
+pub fn focus_next() {
+    DOCUMENT.lock().unwrap().focus_request = Some(FOCUSREQUEST::NEXT);
+}
+
+/**
+ * Moves the focus to the previous element in tab order, same as Shift + Tab.
+ * Applied after the current event. Uses DOCUMENT lock()
+ */
+pub fn focus_prev() {
+    DOCUMENT.lock().unwrap().focus_request = Some(FOCUSREQUEST::PREV);
+}
+
+/**
+ * Focuses the first element in tab order whose Component has the given key.
+ * Applied after the current event. Uses DOCUMENT lock()
+ */
+pub fn focus_by_key(key: &str) {
+    DOCUMENT.lock().unwrap().focus_request = Some(FOCUSREQUEST::KEY(key.to_string()));
+}
+
+/**
+ * returns the key of the Component of the focused element.
+ * None if no element is focused or it has no key. Uses DOCUMENT lock()
+ */
+pub fn current_focus() -> Option<String> {
+    DOCUMENT.lock().unwrap().focused_key()
+}
+
+/**
+ * Sets the keymap of the app. Its bindings are active everywhere, after the ones attached to Views.
+ */
+pub fn set_keymap(keymap: Keymap) {
+    let mut document = DOCUMENT.lock().unwrap();
+    document.keymap = Some(keymap);
+    document.changed = true;
+}
+
+/**
+ * returns (keys, description) of the active bindings, innermost first. Bindings hidden by an inner keymap are left out.
+ * Taken at the last render or focus change. Uses DOCUMENT lock()
+ */
+pub fn active_bindings() -> Vec<(String, String)> {
+    keymap::list_bindings(&DOCUMENT.lock().unwrap().active_keymaps)
+}
+
+/**
+ * Replaces the current theme. The whole app is re-rendered with the new colors.
+ */
+pub fn set_theme(theme: Theme) {
+    let mut document = DOCUMENT.lock().unwrap();
+    document.theme = theme;
+    document.changed = true;
+}
+
+/**
+ * returns the currently installed theme
+ */
+pub fn get_theme() -> Theme {
+    DOCUMENT.lock().unwrap().theme
+}
+
+/**
+ * Same as `run`, but installs the given theme first
+ */
+pub fn run_with_theme(app: impl Component, theme: Theme) {
+    DOCUMENT.lock().unwrap().theme = theme;
+    run(app);
+}
+
+/**
+ * Registers a callback called once `run` exits, whether by 'q', a signal, an error or a panic.
+ * It is called after the terminal is restored, hence it can print
+ */
+pub fn on_exit<F: FnMut() + Send + 'static>(handler: F) {
+    EXIT_HANDLERS.lock().unwrap().push(Box::new(handler));
+}
+
+/**
+ * Sets the handler for SUSPEND (Ctrl-Z), RESUME, INTERRUPT (Ctrl-C) and TERMINATE. Called on the main loop, not inside the signal handler.
+ * Return true to skip the default: restoring the terminal and stopping for SUSPEND, drawing again for RESUME and exiting for the rest.
+ * Replaces the previous handler. Do not call it from the handler
+ */
+pub fn on_signal<F: FnMut(SIGNAL) -> bool + Send + 'static>(handler: F) {
+    *SIGNAL_HANDLER.lock().unwrap() = Some(Box::new(handler));
+}
+
 /**
  * Takes in a Component as input and call it
 */
@@ -947,11 +1634,13 @@ pub fn run(app: impl Component) {
     let _ = std::fs::write("debug.txt", "");
 
     panic::set_hook(Box::new(move |info| {
-        endwin();
-        println!("PANIC: {}", info);
+        restore_terminal();
+        eprintln!("PANIC: {}", info);
+        run_exit_handlers();
     }));
 
     initialize();
+    install_handlers();
 
     let node: Arc<Mutex<dyn Component>> = Arc::new(Mutex::new(app));
 
@@ -973,7 +1662,8 @@ pub fn run(app: impl Component) {
         }
     }
 
-    endwin();
+    restore_terminal();
+    run_exit_handlers();
 }
 
 /**
@@ -988,15 +1678,26 @@ mod test {
         vec,
     };
 
-    use ncurses::{COLOR_MAGENTA, COLOR_RED, endwin};
+    use ncurses::{COLOR_MAGENTA, COLOR_RED, KEY_BTAB, KEY_F0, KEY_MOUSE};
 
     use crate::{
         DOCUMENT,
         components::{text::Text, view::View},
-        initialize,
-        interfaces::{Component, ComponentBuilder, Document},
+        initialize, restore_terminal, theme_root,
+        keymap::{Keymap, conflicts, find_binding, is_prefix, list_bindings, parse_keys},
+        interfaces::{Component, ComponentBuilder, Document, Key, KeyEvent, Modifiers, nearest_color},
+        nmodels::{
+            iview::{IView, RenderBox},
+            keys::{decode_key, decode_key_name},
+            textlayout::{TextLine, layout_text, line_positions, text_cells, truncate_lines},
+        },
         run,
-        styles::{CSSStyle, DIMEN, FLEXDIRECTION, OVERFLOWBEHAVIOUR, STYLE},
+        styles::{
+            CSSStyle, DIMEN, DISPLAY, FLEXDIRECTION, Media, OVERFLOWBEHAVIOUR, STYLE, TEXTALIGN, TRANSPARENT,
+            WHITESPACE, parse_color,
+        },
+        theme::{self, Theme},
+        IViewContent,
         use_state,
     };
 
@@ -1087,7 +1788,7 @@ mod test {
                     padding: "10 10 10 10",
                     boxsizing: "border-box",
                     border: 1,  // assuming true means border width of 1
-                    border_color: COLOR_RED,  // assuming COLOR_RED is an i16 constant
+                    border_color: "red",
                     ..Default::default()
                 },
             ).onfocus(move |_e| {
@@ -1102,11 +1803,259 @@ mod test {
         DOCUMENT.lock().unwrap()._clear_fiber();
     }
 
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("red"), COLOR_RED);
+        assert_eq!(parse_color("bright-red"), 9);
+        assert_eq!(parse_color("17"), 17);
+        assert_eq!(parse_color("transparent"), TRANSPARENT);
+        assert_eq!(parse_color("on-surface"), theme::ON_SURFACE);
+
+        let orange = parse_color("#ff8800");
+        assert_eq!(parse_color("#F80"), orange);
+        assert_eq!(parse_color("rgb(255, 136, 0)"), orange);
+        assert_ne!(parse_color("#000001"), orange);
+        assert_eq!(parse_color("#éé0"), -1);
+        assert_eq!(parse_color("#ff88"), -1);
+
+        assert_eq!(nearest_color((255, 136, 0), 256), 208);
+        assert_eq!(nearest_color((250, 10, 10), 16), 9);
+        assert_eq!(nearest_color((250, 10, 10), 8), COLOR_RED);
+    }
+
+    #[test]
+    fn test_layout_text() {
+        let text = |lines: &Vec<TextLine>| {
+            lines
+                .iter()
+                .map(|line| line.cells.iter().map(|(g, _)| g.as_str()).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        let cells = |txt: &str| text_cells(txt, [].into_iter());
+
+        let lines = layout_text(cells("the quick  brown\nfox jumps"), 10, WHITESPACE::WRAP);
+        assert_eq!(text(&lines), vec!["the quick", "brown fox", "jumps"]);
+
+        let lines = layout_text(cells("abcdefghij klm\nno"), 4, WHITESPACE::default());
+        assert_eq!(text(&lines), vec!["abcd", "efgh", "ij", "klm", "no"]);
+        assert!(lines[3].last);
+
+        let mut lines = layout_text(cells("a long line\nb"), 6, WHITESPACE::NOWRAP);
+        truncate_lines(&mut lines, 6, 1);
+        assert_eq!(text(&lines), vec!["a lon…"]);
+
+        let lines = layout_text(cells("a b c d"), 5, WHITESPACE::WRAP);
+        assert_eq!(line_positions(&lines[0], 6, TEXTALIGN::RIGHT), vec![1, 2, 3, 4, 5]);
+        assert_eq!(line_positions(&lines[0], 6, TEXTALIGN::JUSTIFY), vec![0, 1, 3, 4, 5]);
+
+        let spans = text_cells("", ["ab", "c"].into_iter());
+        let spans: Vec<_> = spans.iter().map(|(g, idx)| (g.as_str(), *idx)).collect();
+        assert_eq!(spans, vec![("a", 0), ("b", 0), ("c", 1)]);
+
+        // wide characters take two columns and clusters are never split
+        let lines = layout_text(cells("日本語テキスト"), 5, WHITESPACE::WRAP);
+        assert_eq!(text(&lines), vec!["日本", "語テ", "キス", "ト"]);
+        assert_eq!(lines[0].width(), 4);
+
+        let family = "👨\u{200d}👩\u{200d}👧";
+        let mut lines = layout_text(cells(&format!("ok {family}{family}")), 20, WHITESPACE::NOWRAP);
+        assert_eq!(lines[0].cells.len(), 5);
+        truncate_lines(&mut lines, 6, 1);
+        assert_eq!(text(&lines), vec![format!("ok {family}…")]);
+    }
+
+    #[test]
+    fn test_decode_key() {
+        let decode = |codes: &[i32]| {
+            let mut rest = codes[1..].iter().copied();
+            decode_key(codes[0], &mut || rest.next().unwrap_or(-1), &mut |_| {})
+        };
+        assert_eq!(decode(&[b'j' as i32]), KeyEvent::new(Key::Char('j'), Modifiers::NONE));
+        assert_eq!(decode(&[19]), KeyEvent::new(Key::Char('s'), Modifiers::CTRL));
+        assert_eq!(decode(&[27, b'x' as i32]), KeyEvent::new(Key::Char('x'), Modifiers::ALT));
+        assert_eq!(decode(&[27, 2]), KeyEvent::new(Key::Char('b'), Modifiers::CTRL | Modifiers::ALT));
+        assert_eq!(decode(&[27]), KeyEvent::new(Key::Esc, Modifiers::NONE));
+        let mut ungot = vec![];
+        let mut rest = [KEY_MOUSE].into_iter();
+        let event = decode_key(27, &mut || rest.next().unwrap_or(-1), &mut |ch| ungot.push(ch));
+        assert_eq!((event, ungot), (KeyEvent::new(Key::Esc, Modifiers::NONE), vec![KEY_MOUSE]));
+        assert_eq!(decode(&[0xC3, 0xA9]), KeyEvent::new(Key::Char('é'), Modifiers::NONE));
+        assert_eq!(decode(&[KEY_BTAB]).key, Key::Tab);
+        assert!(decode(&[KEY_BTAB]).shift());
+        assert_eq!(decode(&[KEY_F0 + 5]).key, Key::F(5));
+
+        assert_eq!(decode_key_name("kUP5"), Some(KeyEvent::new(Key::Up, Modifiers::CTRL)));
+        assert_eq!(
+            decode_key_name("kDC4"),
+            Some(KeyEvent::new(Key::Delete, Modifiers::SHIFT | Modifiers::ALT))
+        );
+        assert_eq!(decode_key_name("kUP"), None);
+        assert_eq!(decode_key_name("kxIN"), None);
+    }
+
+    #[test]
+    fn test_keymap() {
+        let key = |c: char, modifiers: Modifiers| KeyEvent::new(Key::Char(c), modifiers);
+        assert_eq!(parse_keys("ctrl+S"), vec![key('s', Modifiers::CTRL)]);
+        assert_eq!(parse_keys("g g"), vec![key('g', Modifiers::NONE), key('g', Modifiers::NONE)]);
+        assert_eq!(parse_keys("shift+a"), vec![key('A', Modifiers::NONE)]);
+        assert_eq!(parse_keys("alt++"), vec![key('+', Modifiers::ALT)]);
+        assert_eq!(
+            parse_keys("ctrl+alt+up f5"),
+            vec![
+                KeyEvent::new(Key::Up, Modifiers::CTRL | Modifiers::ALT),
+                KeyEvent::new(Key::F(5), Modifiers::NONE)
+            ]
+        );
+
+        let inner = Keymap::new().bind("ctrl+s", "inner save", |_| {});
+        let outer = Keymap::new()
+            .bind("g g", "top", |_| {})
+            .bind("ctrl+s", "save", |_| {});
+        let keymaps = vec![inner, outer];
+        assert!(is_prefix(&keymaps, &parse_keys("g")));
+        assert!(!is_prefix(&keymaps, &parse_keys("g g")));
+        assert_eq!(find_binding(&keymaps, &parse_keys("ctrl+s")).unwrap().description, "inner save");
+        assert!(find_binding(&keymaps, &parse_keys("g")).is_none());
+        assert_eq!(
+            list_bindings(&keymaps),
+            vec![
+                ("ctrl+s".to_string(), "inner save".to_string()),
+                ("g g".to_string(), "top".to_string())
+            ]
+        );
+
+        let nested = vec![Keymap::new().bind("g", "go", |_| {}), keymaps[1].clone(), keymaps[0].clone()];
+        assert_eq!(
+            conflicts(&nested),
+            vec![
+                "Key bindings g and g g of an outer keymap share a prefix. The shorter one waits for the chord timeout".to_string(),
+                "Key binding ctrl+s hides the one of an outer keymap".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflicting key binding: g g and g")]
+    fn test_keymap_prefix() {
+        let _ = Keymap::new().bind("g", "go", |_| {}).bind("g g", "top", |_| {});
+    }
+
+    #[test]
+    fn test_media() {
+        let narrow = Media::new().max_width(79);
+        assert!(narrow.matches((24, 79)));
+        assert!(!narrow.matches((24, 80)));
+        let tall_wide = Media::new().min_width(120).min_height(40);
+        assert!(tall_wide.matches((40, 120)));
+        assert!(!tall_wide.matches((39, 200)));
+        assert!(Media::new().matches((1, 1)));
+
+        let small = CSSStyle { width: "100%", ..Default::default() };
+        let style = CSSStyle {
+            width: "30%",
+            media: &[(narrow, small)],
+            ..Default::default()
+        }
+        .create_style();
+        assert_eq!(style.media_styles.len(), 1);
+        assert!(style.has_conditional_style());
+
+        let hidden = CSSStyle { display: "none", ..Default::default() };
+        let style = CSSStyle {
+            media: &[(narrow, hidden)],
+            ..Default::default()
+        }
+        .create_style();
+        assert!(!style.is_hidden());
+        assert!(matches!(style.media_styles[0].1[..], [STYLE::DISPLAY(DISPLAY::NONE)]));
+    }
+
+    #[test]
+    fn test_child_point() {
+        // child placed at (5, 10) of its parent with its first 2 rows scrolled out
+        let placed = RenderBox { toplefty: 5, topleftx: 10, bottomrighty: 8, bottomrightx: 19 };
+        let shown = RenderBox { toplefty: 2, topleftx: 0, bottomrighty: 5, bottomrightx: 9 };
+        assert_eq!(placed.child_point(&shown, (5, 10)), (2, 0));
+        assert_eq!(placed.child_point(&shown, (6, 12)), (3, 2));
+        let whole = RenderBox { toplefty: 0, topleftx: 0, bottomrighty: 3, bottomrightx: 9 };
+        assert_eq!(placed.child_point(&whole, (6, 12)), (1, 2));
+    }
+
+    #[test]
+    fn test_focus_order() {
+        fn focused(document: &Document) -> Option<i32> {
+            document.focused_element().map(|iview| iview.lock().unwrap().id)
+        }
+        let mut document = DOCUMENT.lock().unwrap();
+        document.taborder.clear();
+        document.tabindex = 0;
+        [(1, "a", ""), (2, "b", "g"), (3, "c", "g"), (4, "d", "")]
+            .into_iter()
+            .for_each(|(id, key, group)| {
+                let mut iview = IView::default();
+                iview.id = id;
+                iview.style.focus_group = group.to_string();
+                document.insert_tab_element(Arc::new(Mutex::new(iview)), Some(key.to_string()));
+            });
+        assert_eq!(focused(&document), None);
+
+        document.advance_tab();
+        assert_eq!(focused(&document), Some(1));
+        (0..3).for_each(|_| {
+            document.advance_tab();
+        });
+        assert_eq!(focused(&document), Some(4));
+        document.advance_tab();
+        assert_eq!(focused(&document), None);
+        document.advance_tab();
+        assert_eq!(focused(&document), Some(1));
+
+        // back from the first one goes through nothing focused to the last one
+        document.stepback_tab();
+        assert_eq!(focused(&document), None);
+        document.stepback_tab();
+        assert_eq!(focused(&document), Some(4));
+
+        document.focus_key("c");
+        assert_eq!(focused(&document), Some(3));
+        document.focus_key("missing");
+        assert_eq!(focused(&document), Some(3));
+        assert!(document.move_in_group(true).is_some());
+        assert_eq!(focused(&document), Some(2));
+        document.move_in_group(false);
+        assert_eq!(focused(&document), Some(3));
+        document.focus_key("a");
+        assert!(document.move_in_group(true).is_none());
+        assert_eq!(focused(&document), Some(1));
+
+        document.taborder.clear();
+        document.tabindex = 0;
+    }
+
+    #[test]
+    fn test_theme_inherited() {
+        let text = Text::new("plain".to_string(), CSSStyle::default()).__base__().unwrap();
+        let root = theme_root();
+        root.lock().unwrap().content = IViewContent::CHIDREN(vec![text.clone()]);
+
+        let theme = Theme::light();
+        let prev = std::mem::replace(&mut DOCUMENT.lock().unwrap().theme, theme);
+        let res = root.lock().unwrap().__init__(24, 80, -1, -1, -1, 0);
+        DOCUMENT.lock().unwrap().theme = prev;
+        assert!(res.is_ok());
+
+        let text = text.lock().unwrap();
+        assert_eq!(text.color, theme.on_background);
+        assert_eq!(text.background_color, theme.background);
+        assert_eq!(text.border_color, theme.border);
+    }
+
     #[test]
     fn test_create_tree1() {
         let _ = std::fs::write("debug.txt", "");
         panic::set_hook(Box::new(|info| {
-            endwin();
+            restore_terminal();
             LOGLn!("{}", info);
         }));
 
@@ -9,19 +9,45 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use unicode_segmentation::UnicodeSegmentation;
+
 use ncurses::{
-    box_, copywin, delwin, mvwprintw, newpad, newwin, ungetch, wattroff, wattron, wbkgd, wprintw, BUTTON1_PRESSED, BUTTON2_PRESSED, BUTTON4_PRESSED, BUTTON5_PRESSED, BUTTON_SHIFT, COLOR_BLACK, COLOR_MAGENTA, COLOR_PAIR, KEY_DOWN, KEY_LEFT, KEY_RIGHT, KEY_UP, WINDOW
+    copywin, getmaxyx, delwin, mvwprintw, newpad, newwin, mvwaddstr, wattroff, wattron, wbkgd, wprintw, BUTTON1_PRESSED, BUTTON2_PRESSED, BUTTON4_PRESSED, BUTTON5_PRESSED, BUTTON_SHIFT, COLOR_BLACK, COLOR_MAGENTA, COLOR_PAIR, attr_t, KEY_DOWN, KEY_END, KEY_HOME, KEY_LEFT, KEY_NPAGE, KEY_PPAGE, KEY_RIGHT, KEY_UP, WINDOW
 };
 
 use crate::{
     DOCUMENT, LOG, LOGLn, REMOVEINDEX,
-    interfaces::{BASICSTRUCT, Component, EVENT, IViewContent},
+    keymap::Keymap,
+    components::richtext::Span,
+    interfaces::{
+        BASICSTRUCT, Component, EVENT, ElementRef, FOCUSREQUEST, FocusScopeState, IViewContent, Rect, SCROLLREQUEST, ScrollDrag,
+        ScrollHandle,
+    },
+    nmodels::textlayout::{
+        TextLine, cells_width, grapheme_width, layout_text, line_positions, text_cells, truncate_lines,
+    },
     styles::{
-        BOXSIZING, CSSStyle, DIMEN, FIT_CONTENT, FLEXDIRECTION, OVERFLOWBEHAVIOUR, POSITION, STYLE,
-        Style, TRANSPARENT,
+        BORDER_ATTRIBUTES, BOXSIZING, CSSStyle, DIMEN, FIT_CONTENT, FLEXDIRECTION, KEY_CTRL_END,
+        KEY_CTRL_HOME,
+        OVERFLOWBEHAVIOUR, POSITION, STRIKETHROUGH, STYLE, Style, TEXTALIGN, TEXTOVERFLOW,
+        WHITESPACE,
+        ncurses_attributes,
     },
 };
 
+/** Adds combining long stroke overlay after each grapheme cluster */
+fn strike_through(txt: &str) -> String {
+    txt.graphemes(true)
+        .flat_map(|g| {
+            if g.chars().all(char::is_whitespace) {
+                vec![g]
+            } else {
+                vec![g, "\u{0336}"]
+            }
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 pub(crate) struct RenderBox {
     pub(crate) toplefty: i32,
@@ -44,11 +70,11 @@ impl RenderBox {
         self.bottomrightx = self.bottomrightx.max(other.bottomrightx);
         self.bottomrighty = self.bottomrighty.max(other.bottomrighty);
     }
-    pub(crate) fn add_to_all(&mut self, num: i32) {
-        self.topleftx += num;
-        self.toplefty += num;
-        self.bottomrightx += num;
-        self.bottomrighty += num;
+    pub(crate) fn offset(&mut self, y: i32, x: i32) {
+        self.toplefty += y;
+        self.topleftx += x;
+        self.bottomrighty += y;
+        self.bottomrightx += x;
     }
 
     /***
@@ -58,17 +84,60 @@ impl RenderBox {
         (point.0 >= self.toplefty && point.0 <= self.bottomrighty)
             && (point.1 >= self.topleftx && point.1 <= self.bottomrightx)
     }
+
+    /***
+     * Converts a point inside this box to the window of the child placed at it, border included.
+     * render_box is the part of the child shown, it does not start at 0 when the child is cut by the scroll
+     */
+    pub(crate) fn child_point(&self, render_box: &RenderBox, point: (i32, i32)) -> (i32, i32) {
+        (
+            point.0 - self.toplefty + render_box.toplefty,
+            point.1 - self.topleftx + render_box.topleftx,
+        )
+    }
+}
+
+/** Where a static child is placed inside its parent, before the scroll is applied */
+struct Placement {
+    /** cursor when the child was reached, after wrapping if the child crossed the end */
+    start: (i32, i32),
+    /** top left of the child along with its margin and top, left */
+    origin: (i32, i32),
+    /** far corner used to check whether the child is scrolled out */
+    reach: (i32, i32),
 }
 
+/** IView whose size changed and its new (height, width) */
+pub(crate) type Resized = (Arc<Mutex<IView>>, (i32, i32));
+
 #[derive(Default)]
 pub(crate) struct IView {
     pub(crate) content: IViewContent,
     pub(crate) style: Style,
     pub(crate) children: Vec<Arc<Mutex<dyn Component>>>, // will be neglected if TViewContent::TEXT
+    /** Styled pieces of the TEXT content. Empty for plain Text */
+    pub(crate) spans: Vec<Span>,
+    /** TEXT content broken into lines. Calculated in __init__ */
+    text_lines: Vec<TextLine>,
     pub(crate) parent: Option<Arc<Mutex<IView>>>,
     pub(crate) basic_struct: Option<BASICSTRUCT>,
     pub(crate) id: i32,
     pub(crate) focused: bool,
+    /** mouse is over this IView */
+    pub(crate) hovered: bool,
+    /** mouse button is pressed over this IView */
+    pub(crate) pressed: bool,
+    /** Style without any pseudo-class style applied. Only kept if the style has any pseudo-class style */
+    base_style: Option<Style>,
+
+    /** Colors after resolving transparency and theme roles. Calculated in __init__ */
+    pub(crate) background_color: i16,
+    pub(crate) border_color: i16,
+    /** Color of each side of the border: top, right, bottom, left */
+    border_side_colors: [i16; 4],
+    pub(crate) color: i16,
+    /** Text attributes after inheriting from parent. Calculated in __init__ */
+    attributes: u32,
 
     height: i32,
     width: i32,
@@ -92,9 +161,26 @@ pub(crate) struct IView {
     /**Extra above the content height */
     extray: i32,
     flex_wrap_on: bool,
+    /** Width in which a TEXT can wrap when its width depends on the text itself. FIT_CONTENT if not limited */
+    available_width: i32,
 
     scrollx: i32,
     scrolly: i32,
+    /** Maximum of scrolly in the last layout. Used by stick_to_bottom */
+    last_max_scrolly: i32,
+    /** ScrollHandles attached directly and through an ElementRef */
+    pub(crate) scroll_handles: Vec<ScrollHandle>,
+    pub(crate) element_ref: Option<ElementRef>,
+    /** Bindings active while this IView or one inside it is focused */
+    pub(crate) keymap: Option<Keymap>,
+    /** key of the Component yielding this IView. Set by the parent in __init__ */
+    pub(crate) key: Option<String>,
+    /** (height, width) given to the last onresize */
+    reported_size: Option<(i32, i32)>,
+    /** Set for the IViews of a FocusScope */
+    pub(crate) focus_scope: Option<Arc<Mutex<FocusScopeState>>>,
+    /** top-left of this window in the parent's window, even if partly cut. Set when rendered */
+    origin: (i32, i32),
     /**  Used to check scroll limit. Has Extra Padding values added during init */
     children_height: i32,
     /**  Used to check scroll limit */
@@ -114,17 +200,36 @@ impl IView {
         IView {
             content: IViewContent::TEXT("".to_string()),
             children: vec![],
+            spans: vec![],
+            text_lines: vec![],
             style: Style::default(),
             parent: None,
             basic_struct: None,
             height: FIT_CONTENT,
             width: FIT_CONTENT,
             focused: false,
+            hovered: false,
+            pressed: false,
+            base_style: None,
+            background_color: -1,
+            border_color: -1,
+            border_side_colors: [-1; 4],
+            color: -1,
+            attributes: 0,
             flex_wrap_on: false,
+            available_width: FIT_CONTENT,
             content_height: 0,
             content_width: 0,
             scrollx: 0,
             scrolly: 0,
+            last_max_scrolly: 0,
+            scroll_handles: vec![],
+            element_ref: None,
+            keymap: None,
+            key: None,
+            reported_size: None,
+            focus_scope: None,
+            origin: (0, 0),
             paddingleft: 0,
             paddingtop: 0,
             paddingright: 0,
@@ -196,6 +301,52 @@ impl IView {
     pub(crate) fn fill_box_infos_from_other(&mut self, other: &Self) {
         self.scrollx = other.scrollx;
         self.scrolly = other.scrolly;
+        self.last_max_scrolly = other.last_max_scrolly;
+        self.focused = other.focused;
+        self.hovered = other.hovered;
+        self.pressed = other.pressed;
+        self.reported_size = other.reported_size;
+        // the scope stays mounted
+        if self.focus_scope.is_some() && other.focus_scope.is_some() {
+            self.focus_scope = other.focus_scope.clone();
+        }
+    }
+
+    /**
+     * Restores the base style and applies the media styles matching the terminal size and the pseudo-class styles matching the current state over it.
+     * Order: media, focus, hover, active. Later one wins.
+     * Must run before the parent writes the flex share into the style, so the base style never holds sizes set by the layout.
+     * Uses DOCUMENT lock() if there are media styles
+     */
+    pub(crate) fn resolve_pseudo_style(&mut self) {
+        if self.base_style.is_none() && !self.style.has_conditional_style() {
+            return;
+        }
+        let mut style = self
+            .base_style
+            .get_or_insert_with(|| self.style.clone())
+            .clone();
+
+        if !style.media_styles.is_empty() {
+            let size = DOCUMENT.lock().unwrap().terminal_size;
+            style
+                .media_styles
+                .clone()
+                .into_iter()
+                .filter(|(media, _)| media.matches(size))
+                .for_each(|(_, styles)| styles.into_iter().for_each(|v| style.set_style(v)));
+        }
+        let states = [
+            (self.focused, style.focus_style.clone()),
+            (self.hovered, style.hover_style.clone()),
+            (self.pressed, style.active_style.clone()),
+        ];
+        states.into_iter().for_each(|(on, styles)| {
+            if on {
+                styles.into_iter().for_each(|v| style.set_style(v));
+            }
+        });
+        self.style = style;
     }
 
     /**
@@ -250,10 +401,19 @@ impl IView {
         // init the chidlren and calculate the new dimension if dependent on children
         match &self.content {
             IViewContent::CHIDREN(items) => {
+                // resolve first as pseudo and media styles can change flex
+                items.iter().for_each(|child| {
+                    let mut child = child.lock().unwrap();
+                    if child.style.render {
+                        child.resolve_pseudo_style();
+                    }
+                });
                 // get the children flex sum
                 let total_flex = items
                     .iter()
-                    .fold(0, |prev, child| prev + child.lock().unwrap().style.flex);
+                    .map(|child| child.lock().unwrap())
+                    .filter(|child| !child.style.is_hidden())
+                    .fold(0, |prev, child| prev + child.style.flex);
 
                 let mut cheight_wrap = 0;
                 let mut cwidth_wrap = 0;
@@ -263,17 +423,26 @@ impl IView {
                 (cheight, cwidth, changed) =
                     items.iter().try_fold((0, 0, changed), |prev, child_lk| {
                         let taborder = {
-                            let child = child_lk.lock().unwrap();
+                            let mut child = child_lk.lock().unwrap();
+                            if child.style.is_hidden() {
+                                // takes no space and can not be focused
+                                child.height = 0;
+                                child.width = 0;
+                                i += 1;
+                                return Ok((prev.0, prev.1, prev.2 | child.style.render));
+                            }
                             child.style.taborder
                         };
 
+                        let key = self.children.get(i).and_then(|child| child.lock().unwrap().__key__());
                         // add this to the tab order
                         if taborder >= 0 {
                             let mut document = DOCUMENT.lock().unwrap();
-                            document.insert_tab_element(child_lk.clone());
+                            document.insert_tab_element(child_lk.clone(), key.clone());
                         }
 
                         let mut child = child_lk.lock().unwrap();
+                        child.key = key;
                         // If Child has flex , but no dimension then set the respective dimension as percentage
                         IView::evaluate_flex(&mut child, total_flex, direction);
 
@@ -304,9 +473,10 @@ impl IView {
                         let (childh, childw, changed) = child.__init__(
                             parent_height - to_reduce.0,
                             parent_width - to_reduce.1,
-                            self.style.background_color,
-                            self.style.border_color,
-                            self.style.color,
+                            self.background_color,
+                            self.border_color,
+                            self.color,
+                            self.attributes,
                         )?;
                         let res = if matches!(child.style.position, POSITION::RELATIVE) {
                             prev
@@ -410,20 +580,42 @@ impl IView {
             }
             IViewContent::TEXT(txt) => {
                 if changed {
-                    // update chieght and cwidth
+                    // break the text into lines and update chieght and cwidth
+                    let wrap_width = if self.content_width > 0 {
+                        self.content_width
+                    } else {
+                        self.available_width
+                    };
+                    let cells = text_cells(txt, self.spans.iter().map(|span| span.text.as_str()));
+                    let mut lines = layout_text(cells, wrap_width, self.style.white_space);
+                    let text_width = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+
                     if self.content_width <= 0 {
-                        self.content_width = txt.len() as i32;
+                        self.content_width = if wrap_width > 0 {
+                            text_width.min(wrap_width)
+                        } else {
+                            text_width
+                        };
                     }
 
-                    if self.content_width > 0 {
-                        cheight = ((txt.len() as f32 / self.content_width as f32).ceil() as i32)
-                            .max(self.content_height);
-                        cwidth = self.content_width;
+                    if matches!(self.style.text_overflow, TEXTOVERFLOW::ELLIPSIS)
+                        && !matches!(self.style.overflow, OVERFLOWBEHAVIOUR::SCROLL)
+                    {
+                        truncate_lines(&mut lines, self.content_width, self.content_height);
                     }
 
+                    cheight = (lines.len() as i32).max(self.content_height);
+                    cwidth = lines
+                        .iter()
+                        .map(|line| line.width())
+                        .max()
+                        .unwrap_or(0)
+                        .max(self.content_width);
+
                     if self.content_height == FIT_CONTENT {
                         self.content_height = cheight;
                     }
+                    self.text_lines = lines;
                     // LOGLn!("L: {:p} {} {} {} {} {} ",self, txt, self.content_height, self.content_width, cheight, cwidth);
                 }
             }
@@ -607,6 +799,7 @@ impl IView {
         parent_bg: i16,
         parent_bd: i16,
         parent_color: i16,
+        parent_attributes: u32,
     ) -> Result<(i32, i32, bool), String> {
         // we need to know height and width
 
@@ -670,26 +863,45 @@ impl IView {
                 self.fill_box_infos();
                 if self.content_height != FIT_CONTENT {
                     self.content_height -=
-                        self.paddingbottom + self.paddingtop + (self.style.border * 2);
+                        self.paddingbottom + self.paddingtop + self.style.frame_y();
                     self.content_height = self.content_height.max(0);
                 }
                 if self.content_width != FIT_CONTENT {
                     self.content_width -=
-                        self.paddingleft + self.paddingright + (self.style.border * 2);
+                        self.paddingleft + self.paddingright + self.style.frame_x();
 
                     self.content_width = self.content_width.max(0);
                 }
                 // LOGLn!("{} {}", self.content_width, parent_width);
             }
-            if self.style.background_color == TRANSPARENT {
-                self.style.background_color = parent_bg;
-            }
-            if self.style.border_color == TRANSPARENT {
-                self.style.border_color = parent_bd;
-            }
-            if self.style.color == TRANSPARENT {
-                self.style.color = parent_color;
+            if matches!(self.content, IViewContent::TEXT(_)) {
+                // parent's width limits the text only when the width depends on the text itself
+                self.fill_box_infos();
+                self.available_width = if self.content_width == FIT_CONTENT && parent_width > 0 {
+                    (parent_width
+                        - self.marginleft
+                        - self.marginright
+                        - self.paddingleft
+                        - self.paddingright
+                        - self.style.frame_x())
+                        .max(1)
+                } else {
+                    FIT_CONTENT
+                };
             }
+            let theme = DOCUMENT.lock().unwrap().theme;
+            let resolve = |color: i16, parent: i16| theme.inherit(color, parent);
+            self.background_color = resolve(self.style.background_color, parent_bg);
+            self.border_color = resolve(self.style.border_color, parent_bd);
+            self.border_side_colors = [
+                self.style.border_top_color,
+                self.style.border_right_color,
+                self.style.border_bottom_color,
+                self.style.border_left_color,
+            ]
+            .map(|color| resolve(color, self.border_color));
+            self.color = resolve(self.style.color, parent_color);
+            self.attributes = self.style.inherit_attributes(parent_attributes);
         }
 
         let (cheight, cwidth, changed) = self.calculate_child_dimensions(changed)?;
@@ -708,8 +920,8 @@ impl IView {
                 self.content_width = cwidth;
             }
 
-            self.extrax = self.paddingleft + self.paddingright + (self.style.border * 2);
-            self.extray = self.paddingbottom + self.paddingtop + (self.style.border * 2);
+            self.extrax = self.paddingleft + self.paddingright + self.style.frame_x();
+            self.extray = self.paddingbottom + self.paddingtop + self.style.frame_y();
 
             // update the height and width with padding
             self.height += self.extray;
@@ -717,6 +929,8 @@ impl IView {
 
             self.children_height = cheight + self.extray;
             self.children_width = cwidth + self.extrax;
+
+            self.update_scroll();
             // LOGLn!(
             //     "{:p} {} {} : {} {} {} {} {} {}",
             //     self, self.height, self.width, self.content_height, self.content_width ,self.extrax, self.extray, cheight, cwidth
@@ -745,7 +959,7 @@ impl IView {
         };
 
         if for_event {
-            curr_render_box.add_to_all(self.style.border);
+            curr_render_box.offset(self.style.border_top, self.style.border_left);
         }
 
         if curr_render_box.toplefty < 0 {
@@ -790,14 +1004,14 @@ impl IView {
             FLEXDIRECTION::VERTICAL => {
                 // then lets fill the left space in horizontal direction
                 // below this box
-                let hlen = self.height - (self.style.border * 2) - curr_box.bottomrighty;
-                let wleft = (self.width - (self.style.border * 2) - curr_box.topleftx).max(0) as usize;
+                let hlen = self.height - self.style.frame_y() - curr_box.bottomrighty;
+                let wleft = (self.width - self.style.frame_x() - curr_box.topleftx).max(0) as usize;
                 for i in 1..hlen {
                     let r = mvwprintw(*win, curr_box.bottomrighty + i,curr_box.topleftx , &" ".repeat(wleft));
                 }
                 // next to this box
                 let hleft = curr_box.bottomrighty - curr_box.toplefty + 1;
-                let wlen = (self.width - (self.style.border *  2) - curr_box.bottomrightx - 1).max(0) as usize;
+                let wlen = (self.width - self.style.frame_x() - curr_box.bottomrightx - 1).max(0) as usize;
                 for i in 0..hleft {
                     let r = mvwprintw(*win, curr_box.toplefty + i, curr_box.bottomrightx + 1, &" ".repeat(wlen));
                 }
@@ -805,14 +1019,14 @@ impl IView {
             FLEXDIRECTION::HORIZONTAL => {
                 // then lets fill the left space in vertical direction
                 // below this box
-                let hlen = self.height - (self.style.border * 2) - curr_box.bottomrighty;
-                let wleft = (self.width - (self.style.border * 2) - curr_box.topleftx).max(0) as usize;
+                let hlen = self.height - self.style.frame_y() - curr_box.bottomrighty;
+                let wleft = (self.width - self.style.frame_x() - curr_box.topleftx).max(0) as usize;
                 for i in 1..hlen {
                     let r = mvwprintw(*win, curr_box.bottomrighty + i,curr_box.topleftx , &" ".repeat(wleft));
                 }
                 // next to this box
                 let hleft = curr_box.bottomrighty - curr_box.toplefty + 1;
-                let wlen = (self.width - (self.style.border *  2) - curr_box.bottomrightx - 1).max(0) as usize;
+                let wlen = (self.width - self.style.frame_x() - curr_box.bottomrightx - 1).max(0) as usize;
                 for i in 0..hleft {
                     let r = mvwprintw(*win, curr_box.toplefty + i, curr_box.bottomrightx + 1, &" ".repeat(wlen));
                 }
@@ -821,6 +1035,63 @@ impl IView {
         wattroff(*win, COLOR_PAIR(bg_pair));
     }
 
+    /**
+     * Places a static child at topleft and moves topleft past it along the flex direction.
+     * wrap: (height, width) of the current line of children, used when flex wrap is on
+     */
+    fn place_static_child(
+        &self,
+        child: &IView,
+        topleft: &mut (i32, i32),
+        wrap: &mut (i32, i32),
+        scroll_end_cursor: &(i32, i32),
+    ) -> Placement {
+        let mut start = *topleft;
+        let considered_height = child.height + child.marginbottom + child.margintop;
+        let considered_width = child.width + child.marginleft + child.marginright;
+        let reach = match self.style.flex_direction {
+            FLEXDIRECTION::VERTICAL => {
+                wrap.1 = max(wrap.1, considered_width);
+                if self.flex_wrap_on && topleft.0 + considered_height >= scroll_end_cursor.0 {
+                    let did_this_child_cross = topleft.0 + considered_height > scroll_end_cursor.0;
+                    topleft.0 = self.paddingtop;
+                    topleft.1 += wrap.1;
+                    if did_this_child_cross {
+                        start = *topleft;
+                    }
+                }
+                topleft.0 += child.height;
+                (topleft.0 + child.margintop, topleft.1 + child.marginleft + child.width)
+            }
+            FLEXDIRECTION::HORIZONTAL => {
+                wrap.0 = max(wrap.0, considered_height);
+                if self.flex_wrap_on && topleft.1 + considered_width >= scroll_end_cursor.1 {
+                    let did_this_child_cross = topleft.1 + considered_width > scroll_end_cursor.1;
+                    topleft.1 = self.paddingleft;
+                    topleft.0 += wrap.0;
+                    if did_this_child_cross {
+                        start = *topleft;
+                    }
+                }
+                topleft.1 += child.width;
+                (topleft.0 + child.height + child.margintop, topleft.1 + child.marginleft)
+            }
+        };
+        // only the margins along the direction move the next child
+        match self.style.flex_direction {
+            FLEXDIRECTION::VERTICAL => topleft.0 += child.margintop + child.marginbottom,
+            FLEXDIRECTION::HORIZONTAL => topleft.1 += child.marginleft + child.marginright,
+        }
+        Placement {
+            start,
+            origin: (
+                start.0 + child.margintop + child.top,
+                start.1 + child.marginleft + child.left,
+            ),
+            reach,
+        }
+    }
+
     /** Walks through the children while maintaining their position inside this component
      * if event is not None, then calls the handler else renders the children
      * renders the children which have their:  min_z_index <= z_index <= max_z_index
@@ -837,13 +1108,13 @@ impl IView {
         mut event_opt: Option<&mut EVENT>,
     ) -> Result<RenderBox, String> {
         let scroll_end_cursor = (
-            self.scrolly + self.content_height + self.extray - (self.style.border * 2),
-            self.scrollx + self.content_width + self.extrax - (self.style.border * 2),
+            self.scrolly + self.content_height + self.extray - self.style.frame_y(),
+            self.scrollx + self.content_width + self.extrax - self.style.frame_x(),
         );
 
         let mut last_cursor_with_border = last_cursor.clone();
-        last_cursor_with_border.0 += self.style.border * 2;
-        last_cursor_with_border.1 += self.style.border * 2;
+        last_cursor_with_border.0 += self.style.frame_y();
+        last_cursor_with_border.1 += self.style.frame_x();
 
         let direction = &self.style.flex_direction;
 
@@ -854,8 +1125,7 @@ impl IView {
             bottomrighty: 0,
         };
 
-        let mut cheight_wrap = 0;
-        let mut cwidth_wrap = 0;
+        let mut wrap = (0, 0);
 
         let mut actualx = 0;
         let mut is_event = false;
@@ -876,7 +1146,10 @@ impl IView {
             // gets the width covered by the child
             let is_static = {
                 let child = child_lk.lock().unwrap();
-                if child.style.z_index > max_z_index || child.style.z_index < min_z_index {
+                if child.style.z_index > max_z_index
+                    || child.style.z_index < min_z_index
+                    || child.style.is_hidden()
+                {
                     return Ok::<(), String>(());
                 }
                 matches!(child.style.position, POSITION::STATIC)
@@ -890,73 +1163,20 @@ impl IView {
                     return Ok(());
                 }
 
-                let mut prevtopleft = topleft.clone();
-                let considerh;
-                let considerw; // height and width if this child is considered
-                let margin = {
+                let placement = {
                     let child = child_lk.lock().unwrap();
-                    let considered_height = child.height + child.marginbottom + child.margintop;
-                    let considered_width = child.width + child.marginleft + child.marginright;
-                    match direction {
-                        FLEXDIRECTION::VERTICAL => {
-                            cwidth_wrap = max(cwidth_wrap, considered_width);
-                            if self.flex_wrap_on
-                                && topleft.0 + considered_height >= scroll_end_cursor.0
-                            {
-                                let did_this_child_cross =
-                                    topleft.0 + considered_height > scroll_end_cursor.0;
-                                topleft.0 = self.paddingtop;
-                                topleft.1 += cwidth_wrap;
-                                if did_this_child_cross {
-                                    prevtopleft = topleft.clone();
-                                }
-                            }
-                            topleft.0 += child.height;
-                            considerh = topleft.0 + child.margintop;
-                            considerw = topleft.1 + child.marginleft + child.width;
-                        }
-                        FLEXDIRECTION::HORIZONTAL => {
-                            cheight_wrap = max(cheight_wrap, considered_height);
-                            if self.flex_wrap_on
-                                && topleft.1 + considered_width >= scroll_end_cursor.1
-                            {
-                                let did_this_child_cross =
-                                    topleft.1 + considered_width > scroll_end_cursor.1;
-                                topleft.1 = self.paddingleft;
-                                topleft.0 += cheight_wrap;
-                                if did_this_child_cross {
-                                    prevtopleft = topleft.clone();
-                                }
-                            }
-                            topleft.1 += child.width;
-                            considerh = topleft.0 + child.height + child.margintop;
-                            considerw = topleft.1 + child.marginleft;
-                        }
-                    }
-                    topleft.0 += child.margintop;
-                    topleft.1 += child.marginleft;
-                    (
-                        child.margintop,
-                        child.marginbottom,
-                        child.marginleft,
-                        child.marginright,
-                        child.top,
-                        child.left,
-                    )
+                    self.place_static_child(&child, topleft, &mut wrap, &scroll_end_cursor)
                 };
 
-                if prevtopleft.0 >= scroll_end_cursor.0 || prevtopleft.1 >= scroll_end_cursor.1 {
+                if placement.start.0 >= scroll_end_cursor.0 || placement.start.1 >= scroll_end_cursor.1 {
                     return Ok(());
                 }
 
-                if !(considerh + self.style.border < self.scrolly
-                    || considerw + self.style.border < self.scrollx)
+                if !(placement.reach.0 + self.style.border_top < self.scrolly
+                    || placement.reach.1 + self.style.border_left < self.scrollx)
                 {
                     // if visible is set true then its scrollx and scrolly will already be 0
 
-                    prevtopleft.0 += margin.0 + margin.4;
-                    prevtopleft.1 += margin.2 + margin.5;
-
                     // either within the limits or is not static
                     let (mut render_box, child_win) = {
                         let mut child = child_lk.lock().unwrap();
@@ -988,21 +1208,25 @@ impl IView {
                     if let Some(event) = &mut event_opt {
                         let curr_box = self.corrected_render_box(
                             &mut render_box,
-                            &prevtopleft,
+                            &placement.origin,
                             &last_cursor_with_border,
                             true,
                         );
 
                         // if self.style.border_color == COLOR_BLACK || self.style.color == COLOR_MAGENTA {
-                        // LOGLn!("{:?} {:?} {:?} {:?} {}", render_box, prevtopleft, event, curr_box, self.style.border);
+                        // LOGLn!("{:?} {:?} {:?} {:?} {}", render_box, prevtopleft, event, curr_box, self.style.border_top);
                         // }
                         // now check whether this box fells under the event constraints
                         if curr_box.is_inside((event.clienty, event.clientx)) {
-                            event.clientx -= curr_box.topleftx;
-                            event.clienty -= curr_box.toplefty;
+                            (event.clienty, event.clientx) =
+                                curr_box.child_point(&render_box, (event.clienty, event.clientx));
                             let mut child = child_lk.lock().unwrap();
-                            if matches!(child.style.overflow, OVERFLOWBEHAVIOUR::SCROLL) {
-                                DOCUMENT.lock().unwrap().set_active(child_lk.clone());
+                            if !event.locating {
+                                let mut document = DOCUMENT.lock().unwrap();
+                                document.mouse_path.push(child_lk.clone());
+                                if matches!(child.style.overflow, OVERFLOWBEHAVIOUR::SCROLL) {
+                                    document.set_active(child_lk.clone());
+                                }
                             }
                             child.__handle_mouse_event__(event)?;
                             // now call child's event_handler
@@ -1012,15 +1236,20 @@ impl IView {
                     } else {
                         let mut curr_box = self.corrected_render_box(
                             &mut render_box,
-                            &prevtopleft,
+                            &placement.origin,
                             &last_cursor,
                             false,
                         );
 
-                        curr_box.add_to_all(self.style.border);
+                        curr_box.offset(self.style.border_top, self.style.border_left);
 
                         LOGLn!("{:?}", curr_box);
 
+                        child_lk.lock().unwrap().origin = (
+                            curr_box.toplefty - render_box.toplefty,
+                            curr_box.topleftx - render_box.topleftx,
+                        );
+
                         self.fill_remaining(win, &curr_box, direction, bg_pair);
 
                         copywin(
@@ -1039,18 +1268,6 @@ impl IView {
                         curr_render_box.update(&curr_box);
                     }
                 }
-                match direction {
-                    FLEXDIRECTION::VERTICAL => {
-                        topleft.0 += margin.1;
-                        topleft.1 -= margin.2; // since this was added but we are not going in this direction
-                        // hence remove the left margin
-                    }
-                    FLEXDIRECTION::HORIZONTAL => {
-                        topleft.1 += margin.3;
-                        topleft.0 -= margin.0; // since this was added but we are not going in this direction
-                        // hence remove the top margin
-                    }
-                }
             } else {
                 let (margin, (mut render_box, child_win)) = {
                     let mut child = child_lk.lock().unwrap();
@@ -1091,14 +1308,26 @@ impl IView {
                 if let Some(event) = &mut event_opt {
                     // now check whether this box fells under the event constraints
                     if curr_box.is_inside((event.clienty, event.clientx)) {
+                        (event.clienty, event.clientx) =
+                            curr_box.child_point(&render_box, (event.clienty, event.clientx));
                         let mut child = child_lk.lock().unwrap();
-                        if matches!(child.style.overflow, OVERFLOWBEHAVIOUR::SCROLL) {
-                            DOCUMENT.lock().unwrap().set_active(child_lk.clone());
+                        if !event.locating {
+                            let mut document = DOCUMENT.lock().unwrap();
+                            document.mouse_path.push(child_lk.clone());
+                            if matches!(child.style.overflow, OVERFLOWBEHAVIOUR::SCROLL) {
+                                document.set_active(child_lk.clone());
+                            }
                         }
                         // now call child's event_handler
                         child.__handle_mouse_event__(event)?;
+                        event.clientx = actualx;
+                        event.clienty = actualy;
                     }
                 } else {
+                    child_lk.lock().unwrap().origin = (
+                        curr_box.toplefty - render_box.toplefty,
+                        curr_box.topleftx - render_box.topleftx,
+                    );
                     copywin(
                         child_win,
                         *win,
@@ -1133,8 +1362,8 @@ impl IView {
     pub(crate) fn __render__(&mut self) -> Result<(RenderBox, WINDOW), String> {
         let mut topleft = (self.paddingtop, self.paddingleft); // virtual screen
         let mut last_cursor = (
-            self.content_height + self.extray - (self.style.border * 2) - 1, // do not consider the borderwidth in the lastcursor of this window
-            self.content_width + self.extrax - (self.style.border * 2) - 1,
+            self.content_height + self.extray - self.style.frame_y() - 1, // do not consider the borderwidth in the lastcursor of this window
+            self.content_width + self.extrax - self.style.frame_x() - 1,
         );
         // do not consider the padding along the direction
 
@@ -1164,7 +1393,7 @@ impl IView {
             DOCUMENT
                 .lock()
                 .unwrap()
-                .get_color_pair(self.style.border_color, self.style.background_color)
+                .get_color_pair(self.border_color, self.background_color)
         };
 
         match &self.content {
@@ -1192,12 +1421,8 @@ impl IView {
                     // then we need to render this window itself
                     // so background must be updated
                     wbkgd(*win, ' ' as u32 | COLOR_PAIR(border_color));
-                    if self.style.border > 0 {
-                        // LOG!("{} {}", self.style.border_color, self.style.background_color);
-                        wattron(*win, COLOR_PAIR(border_color)); // setting border_pair
-                        box_(*win, 0, 0);
-                        wattroff(*win, COLOR_PAIR(border_color)); // setting border_pair
-                    }
+                    self.draw_border(*win);
+                    self.draw_scrollbars(*win);
                 }
 
                 curr_render_box.update(&self.render_children(
@@ -1211,7 +1436,7 @@ impl IView {
                     None,
                 )?);
             }
-            IViewContent::TEXT(txt) => {
+            IViewContent::TEXT(_) => {
                 let BASICSTRUCT::WIN(win_t) = &basicstr else {
                     return Err(format!("NO WINDOW found for View {:p}", self));
                 };
@@ -1223,15 +1448,12 @@ impl IView {
                         DOCUMENT
                             .lock()
                             .unwrap()
-                            .get_color_pair(self.style.color, self.style.background_color)
+                            .get_color_pair(self.color, self.background_color)
                     };
 
                     wbkgd(*win, ' ' as u32 | COLOR_PAIR(border_color));
-                    if self.style.border > 0 {
-                        wattron(*win, COLOR_PAIR(border_color)); // setting border_pair
-                        box_(*win, 0, 0);
-                        wattroff(*win, COLOR_PAIR(border_color)); // setting off border_pair
-                    }
+                    self.draw_border(*win);
+                    self.draw_scrollbars(*win);
                     // LOGLn!("{} {} {:?} {:?}", self.children_height, self.children_width, topleft, last_cursor);
                     let pad = newpad(self.children_height, self.children_width);
 
@@ -1239,23 +1461,19 @@ impl IView {
                     // so background must be updated
                     wbkgd(pad, ' ' as u32 | COLOR_PAIR(text_color));
 
-                    wattron(pad, COLOR_PAIR(text_color)); // setting text_pair
-                    // display the text at curootrrent top and left
-                    let res = mvwprintw(pad, 0, 0, &txt);
-                    if let Err(_) = res {
-                        LOGLn!("Warning: NULL Error while rendering Text View {:p}", self);
-                    };
-                    wattroff(pad, COLOR_PAIR(text_color)); // setting off text_pair
+                    // display the text lines at current top and left
+                    let visible_width = last_cursor.1 - self.paddingright - topleft.1 + 1;
+                    self.render_text_lines(pad, text_color, (self.scrollx, self.scrollx + visible_width));
 
                     copywin(
                         pad,
                         *win,
                         self.scrolly,
                         self.scrollx,
-                        topleft.0 + self.style.border,
-                        topleft.1 + self.style.border,
-                        last_cursor.0 - self.paddingbottom + self.style.border,
-                        last_cursor.1 - self.paddingright + self.style.border,
+                        topleft.0 + self.style.border_top,
+                        topleft.1 + self.style.border_left,
+                        last_cursor.0 - self.paddingbottom + self.style.border_top,
+                        last_cursor.1 - self.paddingright + self.style.border_left,
                         0,
                     );
 
@@ -1275,83 +1493,613 @@ impl IView {
         Ok((curr_render_box, *win))
     }
 
+    /**
+     * Draws the sides of the border which are on, each in its own color.
+     * Horizontal sides take the full width, corners are drawn only where two sides meet.
+     * Uses DOCUMENT lock()
+     */
+    fn draw_border(&self, win: WINDOW) {
+        if !self.style.has_border() {
+            return;
+        }
+        let (mut height, mut width) = (0, 0);
+        getmaxyx(win, &mut height, &mut width);
+        if height <= 0 || width <= 0 {
+            return;
+        }
+
+        let [top, right, bottom, left] = {
+            let mut document = DOCUMENT.lock().unwrap();
+            self.border_side_colors.map(|color| {
+                COLOR_PAIR(document.get_color_pair(color, self.background_color))
+                    | ncurses_attributes(self.attributes & BORDER_ATTRIBUTES)
+            })
+        };
+        let chars = self.style.border_style.chars();
+        let horizontal = chars
+            .horizontal
+            .repeat((width / grapheme_width(&chars.horizontal).max(1)) as usize);
+        let draw = |y: i32, x: i32, txt: &str, attributes: attr_t| {
+            wattron(win, attributes);
+            // writing the bottom right corner of the window returns an error even if it is drawn
+            let _ = mvwaddstr(win, y, x, txt);
+            wattroff(win, attributes);
+        };
+
+        for y in self.style.border_top..(height - self.style.border_bottom) {
+            if self.style.border_left > 0 {
+                draw(y, 0, &chars.vertical, left);
+            }
+            if self.style.border_right > 0 {
+                draw(y, width - 1, &chars.vertical, right);
+            }
+        }
+        if self.style.border_top > 0 {
+            draw(0, 0, &horizontal, top);
+            if self.style.border_left > 0 {
+                draw(0, 0, &chars.top_left, top);
+            }
+            if self.style.border_right > 0 {
+                draw(0, width - 1, &chars.top_right, top);
+            }
+        }
+        if self.style.border_bottom > 0 {
+            draw(height - 1, 0, &horizontal, bottom);
+            if self.style.border_left > 0 {
+                draw(height - 1, 0, &chars.bottom_left, bottom);
+            }
+            if self.style.border_right > 0 {
+                draw(height - 1, width - 1, &chars.bottom_right, bottom);
+            }
+        }
+
+        if let Some((title, align)) = self.style.border_title.as_deref()
+            && self.style.border_top > 0
+        {
+            self.draw_border_caption(win, 0, width, title, *align, (top, self.border_side_colors[0]));
+        }
+        if let Some((footer, align)) = self.style.border_footer.as_deref()
+            && self.style.border_bottom > 0
+        {
+            self.draw_border_caption(win, height - 1, width, footer, *align, (bottom, self.border_side_colors[2]));
+        }
+    }
+
+    /** Maximum value of scrolly */
+    pub(crate) fn max_scrolly(&self) -> i32 {
+        (self.children_height - self.content_height - self.extray).max(0)
+    }
+
+    /** Maximum value of scrollx */
+    pub(crate) fn max_scrollx(&self) -> i32 {
+        (self.children_width - self.content_width - self.extrax).max(0)
+    }
+
+    /**
+     * Applies stick_to_bottom and the requests from the ScrollHandle, once the layout is known.
+     * Publishes the resulting offsets to the handle.
+     */
+    fn update_scroll(&mut self) {
+        if !matches!(self.style.overflow, OVERFLOWBEHAVIOUR::SCROLL) {
+            return;
+        }
+        // was at the bottom before the content changed
+        if self.style.stick_to_bottom && self.scrolly >= self.last_max_scrolly {
+            self.scrolly = self.max_scrolly();
+        }
+
+        for handle in self.scroll_handles.clone() {
+            let requests = std::mem::take(&mut handle.state.lock().unwrap().requests);
+            for request in requests {
+                match request {
+                    SCROLLREQUEST::TO(y, x) => {
+                        self.scrolly = y.unwrap_or(self.scrolly);
+                        self.scrollx = x.unwrap_or(self.scrollx);
+                    }
+                    SCROLLREQUEST::BY(dy, dx) => {
+                        self.scrolly += dy;
+                        self.scrollx += dx;
+                    }
+                    SCROLLREQUEST::END => self.scrolly = self.max_scrolly(),
+                    SCROLLREQUEST::INTOVIEW(key) => {
+                        if let Some((y, x, height, width)) = self.child_box(&key) {
+                            let view_height = self.content_height + self.extray - self.style.frame_y();
+                            let view_width = self.content_width + self.extrax - self.style.frame_x();
+                            // least scroll which shows the child, its start wins if it does not fit
+                            self.scrolly = self.scrolly.min(y).max(y + height - view_height).min(y);
+                            self.scrollx = self.scrollx.min(x).max(x + width - view_width).min(x);
+                        }
+                    }
+                }
+                self.scrolly = self.scrolly.clamp(0, self.max_scrolly());
+                self.scrollx = self.scrollx.clamp(0, self.max_scrollx());
+            }
+
+            let mut state = handle.state.lock().unwrap();
+            state.scrolly = self.scrolly;
+            state.scrollx = self.scrollx;
+            state.max_scrolly = self.max_scrolly();
+            state.max_scrollx = self.max_scrollx();
+        }
+        self.last_max_scrolly = self.max_scrolly();
+    }
+
+    /**
+     * returns (y, x, height, width) of the direct child with the given key, in the same coordinates as scrolly and scrollx.
+     * Follows the placement done by render_children.
+     */
+    fn child_box(&self, key: &str) -> Option<(i32, i32, i32, i32)> {
+        let IViewContent::CHIDREN(icomponents) = &self.content else {
+            return None;
+        };
+        let index = self
+            .children
+            .iter()
+            .position(|child| child.lock().unwrap().__key__().is_some_and(|k| k == key))?;
+
+        let scroll_end_cursor = (
+            self.scrolly + self.content_height + self.extray - self.style.frame_y(),
+            self.scrollx + self.content_width + self.extrax - self.style.frame_x(),
+        );
+        let mut topleft = (self.paddingtop, self.paddingleft);
+        let mut wrap = (0, 0);
+        for (i, child_lk) in icomponents.iter().enumerate() {
+            let child = child_lk.lock().unwrap();
+            if child.style.is_hidden() {
+                if i == index {
+                    return None;
+                }
+                continue;
+            }
+            if !matches!(child.style.position, POSITION::STATIC) {
+                if i == index {
+                    return Some((child.top, child.left, child.height, child.width));
+                }
+                continue;
+            }
+            let placement = self.place_static_child(&child, &mut topleft, &mut wrap, &scroll_end_cursor);
+            if i == index {
+                return Some((placement.origin.0, placement.origin.1, child.height, child.width));
+            }
+        }
+        None
+    }
+
+    /**
+     * Publishes the screen position and size to the ElementRefs in this subtree.
+     * (y, x) is the screen position of this window. generation marks the refs as mounted
+     */
+    pub(crate) fn update_refs(&self, (y, x): (i32, i32), generation: u32) {
+        if let Some(element_ref) = &self.element_ref {
+            let mut state = element_ref.state.lock().unwrap();
+            state.rect = Rect {
+                y,
+                x,
+                height: self.height,
+                width: self.width,
+            };
+            state.generation = generation;
+        }
+        if let IViewContent::CHIDREN(icomponents) = &self.content {
+            icomponents.iter().for_each(|child_lk| {
+                let child = child_lk.lock().unwrap();
+                child.update_refs((y + child.origin.0, x + child.origin.1), generation);
+            });
+        }
+    }
+
+    /** Collects the IViews in the subtree having onresize whose size changed since it was last reported, with their new size */
+    pub(crate) fn collect_resized(&self, resized: &mut Vec<Resized>) {
+        if let IViewContent::CHIDREN(icomponents) = &self.content {
+            icomponents.iter().for_each(|child_lk| {
+                let mut child = child_lk.lock().unwrap();
+                let size = (child.height, child.width);
+                if child.style.onresize.is_some() && child.reported_size != Some(size) {
+                    child.reported_size = Some(size);
+                    resized.push((child_lk.clone(), size));
+                }
+                child.collect_resized(resized);
+            });
+        }
+    }
+
+    /**
+     * Collects the FocusScopes in the subtree, outermost first, along with the focusable elements inside each.
+     * open: scopes holding this IView
+     */
+    pub(crate) fn collect_focus_scopes(
+        &self,
+        open: &mut Vec<Arc<Mutex<FocusScopeState>>>,
+        scopes: &mut Vec<Arc<Mutex<FocusScopeState>>>,
+    ) {
+        if self.style.taborder >= 0 {
+            open.iter().for_each(|scope| scope.lock().unwrap().members.push(self.id));
+        }
+        if let Some(scope) = &self.focus_scope {
+            scope.lock().unwrap().members.clear();
+            open.push(scope.clone());
+            scopes.push(scope.clone());
+        }
+        if let IViewContent::CHIDREN(icomponents) = &self.content {
+            icomponents
+                .iter()
+                .for_each(|child_lk| child_lk.lock().unwrap().collect_focus_scopes(open, scopes));
+        }
+        if self.focus_scope.is_some() {
+            open.pop();
+        }
+    }
+
+    /**
+     * Makes this element the target of the event, with the mouse position relative to its content box, scroll included.
+     * Called while locating the target, hence the innermost element under the mouse is the one left
+     */
+    fn fill_target_info(&self, event: &mut EVENT) {
+        event.offsetx = event.clientx - self.style.border_left - self.paddingleft + self.scrollx;
+        event.offsety = event.clienty - self.style.border_top - self.paddingtop + self.scrolly;
+        event.target_id = self.id;
+        event.target_key = self.key.clone();
+    }
+
+    /** Copies the scroll offsets to the event for its handlers */
+    fn fill_scroll_info(&self, event: &mut EVENT) {
+        event.scrolly = self.scrolly;
+        event.scrollx = self.scrollx;
+        event.max_scrolly = self.max_scrolly();
+        event.max_scrollx = self.max_scrollx();
+    }
+
+    /**
+     * returns the (start, length) of the scrollbar track and the row/column of the gutter in window coordinates.
+     * vertical: the scrollbar along the right side
+     */
+    fn scrollbar_track(&self, vertical: bool) -> (i32, i32, i32) {
+        if vertical {
+            (
+                self.style.border_top,
+                self.content_height + self.extray - self.style.frame_y(),
+                self.content_width + self.extrax - self.style.border_right - 1,
+            )
+        } else {
+            (
+                self.style.border_left,
+                self.content_width + self.extrax - self.style.frame_x(),
+                self.content_height + self.extray - self.style.border_bottom - 1,
+            )
+        }
+    }
+
+    /** returns the (position, length) of the thumb inside the track */
+    fn scrollbar_thumb(&self, vertical: bool) -> (i32, i32) {
+        let (_, track, _) = self.scrollbar_track(vertical);
+        let (viewport, max, scroll) = if vertical {
+            (self.content_height, self.max_scrolly(), self.scrolly)
+        } else {
+            (self.content_width, self.max_scrollx(), self.scrollx)
+        };
+        if max <= 0 || track <= 0 {
+            return (0, track.max(0));
+        }
+        let len = (track * viewport / (viewport + max)).clamp(1, track);
+        let pos = ((track - len) * scroll + max / 2) / max;
+        (pos, len)
+    }
+
+    /** returns the scroll which puts the thumb at the given position of the track */
+    fn scroll_for_thumb(&self, vertical: bool, pos: i32) -> i32 {
+        let (_, track, _) = self.scrollbar_track(vertical);
+        let (_, len) = self.scrollbar_thumb(vertical);
+        let max = if vertical { self.max_scrolly() } else { self.max_scrollx() };
+        if track <= len {
+            return 0;
+        }
+        ((pos * max + (track - len) / 2) / (track - len)).clamp(0, max)
+    }
+
+    /**
+     * Draws the scrollbars in their gutters.
+     * Uses DOCUMENT lock()
+     */
+    fn draw_scrollbars(&self, win: WINDOW) {
+        let scrollbars = [
+            (true, self.style.scrollbar_width() > 0),
+            (false, self.style.scrollbar_height() > 0),
+        ];
+        if !scrollbars.iter().any(|(_, on)| *on) {
+            return;
+        }
+        let pair = {
+            DOCUMENT
+                .lock()
+                .unwrap()
+                .get_color_pair(self.border_color, self.background_color)
+        };
+        wattron(win, COLOR_PAIR(pair));
+        for (vertical, _) in scrollbars.into_iter().filter(|(_, on)| *on) {
+            let (start, track, gutter) = self.scrollbar_track(vertical);
+            let (pos, len) = self.scrollbar_thumb(vertical);
+            for i in 0..track {
+                let ch = if i >= pos && i < pos + len { "█" } else { "░" };
+                let _ = if vertical {
+                    mvwaddstr(win, start + i, gutter, ch)
+                } else {
+                    mvwaddstr(win, gutter, start + i, ch)
+                };
+            }
+        }
+        wattroff(win, COLOR_PAIR(pair));
+    }
+
+    /**
+     * If the press is over a scrollbar, jumps to it when outside the thumb and starts dragging the thumb.
+     * (y, x) relative to this window, (screeny, screenx) on the screen.
+     * returns true if a scrollbar was pressed
+     * Uses DOCUMENT lock()
+     */
+    fn press_scrollbar(&mut self, (y, x): (i32, i32), (screeny, screenx): (i32, i32)) -> bool {
+        for vertical in [true, false] {
+            let on = if vertical {
+                self.style.scrollbar_width() > 0
+            } else {
+                self.style.scrollbar_height() > 0
+            };
+            let (start, track, gutter) = self.scrollbar_track(vertical);
+            let (across, along, screen) = if vertical { (x, y, screeny) } else { (y, x, screenx) };
+            if !on || across != gutter || along < start || along >= start + track {
+                continue;
+            }
+
+            let (pos, len) = self.scrollbar_thumb(vertical);
+            let along = along - start;
+            if along < pos || along >= pos + len {
+                // jump so that the thumb is centered at the press
+                let scroll = self.scroll_for_thumb(vertical, along - len / 2);
+                if vertical {
+                    self.scrolly = scroll;
+                } else {
+                    self.scrollx = scroll;
+                }
+                self.style.render = true;
+            }
+
+            let mut document = DOCUMENT.lock().unwrap();
+            // this IView is the last one found under the mouse
+            if let Some(iview) = document.mouse_path.last().cloned() {
+                document.scroll_drag = Some(ScrollDrag {
+                    iview,
+                    vertical,
+                    start: screen,
+                    start_scroll: if vertical { self.scrolly } else { self.scrollx },
+                });
+            }
+            document.changed |= self.style.render;
+            return true;
+        }
+        false
+    }
+
+    /**
+     * Moves the scroll along with the mouse while dragging the thumb
+     * Uses DOCUMENT lock()
+     */
+    pub(crate) fn drag_scrollbar(&mut self, drag: &ScrollDrag, (screeny, screenx): (i32, i32)) {
+        let (_, track, _) = self.scrollbar_track(drag.vertical);
+        let (_, len) = self.scrollbar_thumb(drag.vertical);
+        if track <= len {
+            return;
+        }
+        let (moved, max) = if drag.vertical {
+            (screeny - drag.start, self.max_scrolly())
+        } else {
+            (screenx - drag.start, self.max_scrollx())
+        };
+        let scroll = (drag.start_scroll + moved * max / (track - len)).clamp(0, max);
+        let curr = if drag.vertical { &mut self.scrolly } else { &mut self.scrollx };
+        if *curr != scroll {
+            *curr = scroll;
+            self.style.render = true;
+            DOCUMENT.lock().unwrap().changed = true;
+        }
+    }
+
+    /**
+     * Draws the caption with a space on either side, between the corners of the row y.
+     * Cut with an ellipsis if it does not fit.
+     * side: attributes and color of the border side it is drawn over
+     * Uses DOCUMENT lock()
+     */
+    fn draw_border_caption(
+        &self,
+        win: WINDOW,
+        y: i32,
+        width: i32,
+        caption: &Span,
+        align: TEXTALIGN,
+        side: (attr_t, i16),
+    ) {
+        // space for the caption along with a space on either side
+        let available = width - self.style.border_x() - 2;
+        if available <= 0 {
+            return;
+        }
+        let cells = text_cells(&caption.text, [].into_iter());
+        let mut lines = layout_text(cells, 0, WHITESPACE::NOWRAP);
+        truncate_lines(&mut lines, available, 1);
+        let Some(line) = lines.first() else {
+            return;
+        };
+        let text: String = line.cells.iter().map(|(g, _)| g.as_str()).collect();
+        let free = available - line.width();
+        let x = self.style.border_left
+            + match align {
+                TEXTALIGN::LEFT | TEXTALIGN::JUSTIFY => 0,
+                TEXTALIGN::CENTER => free / 2,
+                TEXTALIGN::RIGHT => free,
+            };
+
+        let (pair, attributes) = {
+            let mut document = DOCUMENT.lock().unwrap();
+            caption.resolve(
+                &mut document,
+                side.1,
+                self.background_color,
+                self.attributes & BORDER_ATTRIBUTES,
+            )
+        };
+        let caption_attributes = COLOR_PAIR(pair) | ncurses_attributes(attributes);
+
+        wattron(win, side.0);
+        let _ = mvwaddstr(win, y, x, " ");
+        let _ = mvwaddstr(win, y, x + line.width() + 1, " ");
+        wattroff(win, side.0);
+        wattron(win, caption_attributes);
+        let _ = if attributes & STRIKETHROUGH > 0 {
+            mvwaddstr(win, y, x + 1, &strike_through(&text))
+        } else {
+            mvwaddstr(win, y, x + 1, &text)
+        };
+        wattroff(win, caption_attributes);
+    }
+
+    /**
+     * Draws the text lines aligned inside the content width.
+     * Cells are drawn in runs having the same span.
+     * Wide clusters cut by the visible columns [clip.0, clip.1) are drawn as spaces, half of a cluster can not be shown.
+     * Uses DOCUMENT lock()
+     */
+    fn render_text_lines(&self, pad: WINDOW, text_color: i16, clip: (i32, i32)) {
+        let span_attributes = if self.spans.is_empty() {
+            vec![(text_color, self.attributes)]
+        } else {
+            let mut document = DOCUMENT.lock().unwrap();
+            self.spans
+                .iter()
+                .map(|span| {
+                    span.resolve(&mut document, self.color, self.background_color, self.attributes)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for (y, line) in self.text_lines.iter().enumerate() {
+            let positions = line_positions(line, self.content_width, self.style.text_align);
+            let mut start = 0;
+            while start < line.cells.len() {
+                // run of cells with the same span and no gap in between
+                let idx = line.cells[start].1;
+                let mut end = start + 1;
+                while end < line.cells.len()
+                    && line.cells[end].1 == idx
+                    && positions[end] == positions[start] + cells_width(&line.cells[start..end])
+                {
+                    end += 1;
+                }
+
+                let run: String = line.cells[start..end]
+                    .iter()
+                    .zip(&positions[start..end])
+                    .map(|((g, _), x)| {
+                        let w = grapheme_width(g);
+                        let cut = (*x < clip.0 && x + w > clip.0) || (*x < clip.1 && x + w > clip.1);
+                        if cut { " ".repeat(w as usize) } else { g.clone() }
+                    })
+                    .collect();
+                let (pair, attributes) = span_attributes[idx];
+                let run_attributes = COLOR_PAIR(pair) | ncurses_attributes(attributes);
+                wattron(pad, run_attributes);
+                // writing the bottom right corner of the pad returns an error even if it is drawn
+                let _ = if attributes & STRIKETHROUGH > 0 {
+                    mvwaddstr(pad, y as i32, positions[start], &strike_through(&run))
+                } else {
+                    mvwaddstr(pad, y as i32, positions[start], &run)
+                };
+                wattroff(pad, run_attributes);
+                start = end;
+            }
+        }
+    }
+
+    /**
+     * Axis scrolled by the wheel. The one along which the children flow i.e. vertical for a vertical View, horizontal if it wraps into columns.
+     * If there is nothing to scroll along that axis, the other one is used.
+     */
+    fn wheel_vertical(&self) -> bool {
+        let vertical = matches!(self.style.flex_direction, FLEXDIRECTION::VERTICAL) != self.flex_wrap_on;
+        if vertical {
+            self.max_scrolly() > 0 || self.max_scrollx() == 0
+        } else {
+            !(self.max_scrollx() > 0 || self.max_scrolly() == 0)
+        }
+    }
+
+    /** rows and columns visible at once, without the border and scrollbars */
+    fn scroll_page(&self) -> (i32, i32) {
+        (
+            (self.content_height + self.extray - self.style.frame_y()).max(1),
+            (self.content_width + self.extrax - self.style.frame_x()).max(1),
+        )
+    }
+
     pub(crate) fn handle_default(&mut self, event: &mut EVENT) {
-        let mut scroll_direction = -1;
-        let is_scroll_vertical = matches!(self.style.flex_direction, FLEXDIRECTION::VERTICAL);
-        let vertical = (!is_scroll_vertical & self.style.flex_wrap)
-            | (is_scroll_vertical & !self.style.flex_wrap);
+        // (rows, columns) to scroll by
+        let mut delta = (0, 0);
         if let Some(mevent) = &event.mevent {
             if mevent.bstate & BUTTON1_PRESSED as u32 > 0 {
                 // left mouse clicked
+                self.press_scrollbar((event.clienty, event.clientx), (mevent.y, mevent.x));
                 if self.style.taborder >= 0 {
-                    // generate a tab event which will change focus and call handler itself
-                    DOCUMENT.lock().unwrap().next_tab_id = self.id;
-                    ungetch('\t' as i32);
+                    // focus after the event, so that handlers of this event can move it elsewhere
+                    DOCUMENT.lock().unwrap().focus_request = Some(FOCUSREQUEST::ID(self.id));
                 }
             } else if (mevent.bstate & BUTTON2_PRESSED as u32 == 0)
                 && matches!(self.style.overflow, OVERFLOWBEHAVIOUR::SCROLL)
             {
-                if mevent.bstate & BUTTON4_PRESSED as u32 > 0 {
-                    if mevent.bstate & BUTTON_SHIFT as u32 > 0 {
-                        // scroll right
-                        scroll_direction = 3;
-                    } else {
-                        // scroll down
-                        scroll_direction = 1;
-                    }
+                let step = if mevent.bstate & BUTTON4_PRESSED as u32 > 0 {
+                    // scroll up
+                    -self.style.wheel_step
                 } else if mevent.bstate & BUTTON5_PRESSED as u32 > 0 {
-                    if mevent.bstate & BUTTON_SHIFT as u32 > 0 {
-                        // scroll left
-                        scroll_direction = 2;
-                    } else {
-                        // scroll up
-                        scroll_direction = 0;
-                    }
+                    // scroll down
+                    self.style.wheel_step
+                } else {
+                    0
+                };
+                // shift scrolls the other axis
+                if self.wheel_vertical() != (mevent.bstate & BUTTON_SHIFT as u32 > 0) {
+                    delta.0 = step;
+                } else {
+                    delta.1 = step;
                 }
             }
         } else {
-            match event.key {
+            let (page_height, page_width) = self.scroll_page();
+            let vertical = self.wheel_vertical();
+            let along = |amount: i32| if vertical { (amount, 0) } else { (0, amount) };
+            let (start, end) = if vertical {
+                (-self.scrolly, self.max_scrolly() - self.scrolly)
+            } else {
+                (-self.scrollx, self.max_scrollx() - self.scrollx)
+            };
+            delta = match event.key {
                 // natural scrolling
-                KEY_UP => scroll_direction = 1,
-                KEY_RIGHT => scroll_direction = 3,
-                KEY_LEFT => scroll_direction = 2,
-                KEY_DOWN => scroll_direction = 0,
-                _ => {}
-            }
+                KEY_UP => (-1, 0),
+                KEY_DOWN => (1, 0),
+                KEY_LEFT => (0, 1),
+                KEY_RIGHT => (0, -1),
+                KEY_PPAGE => along(-if vertical { page_height } else { page_width }),
+                KEY_NPAGE => along(if vertical { page_height } else { page_width }),
+                KEY_HOME => along(start),
+                KEY_END => along(end),
+                KEY_CTRL_HOME => (-self.scrolly, -self.scrollx),
+                KEY_CTRL_END => (self.max_scrolly() - self.scrolly, self.max_scrollx() - self.scrollx),
+                _ => (0, 0),
+            };
         };
         // LOGLn!("{:p} {} {} {} {}",self, self.scrolly, self.children_height,self.content_height, self.extray);
-        match scroll_direction {
-            0 => {
-                // scroll up
-                if vertical
-                    && self.scrolly < self.children_height - self.content_height - self.extray
-                {
-                    self.scrolly += 1;
-                    self.style.render = true;
-                }
-            }
-            1 => {
-                if vertical && self.scrolly > 0 {
-                    self.scrolly -= 1;
-                    self.style.render = true;
-                }
-            }
-            2 => {
-                if !vertical
-                    && self.scrollx < self.children_width - self.content_width - self.extrax
-                {
-                    self.scrollx += 1;
-                    self.style.render = true;
-                }
-            }
-            3 => {
-                // scroll right
-                if !vertical && self.scrollx > 0 {
-                    self.scrollx -= 1;
-                    self.style.render = true;
-                }
-            }
-            _ => {}
+        let scrolly = (self.scrolly + delta.0).clamp(0, self.max_scrolly().max(self.scrolly));
+        let scrollx = (self.scrollx + delta.1).clamp(0, self.max_scrollx().max(self.scrollx));
+        if (scrolly, scrollx) != (self.scrolly, self.scrollx) {
+            self.scrolly = scrolly;
+            self.scrollx = scrollx;
+            self.style.render = true;
         }
         if self.style.render {
             DOCUMENT.lock().unwrap().changed = true;
@@ -1362,8 +2110,8 @@ impl IView {
     fn transfer_event(&mut self, event: &mut EVENT) -> Result<(), String> {
         let mut topleft = (self.paddingtop, self.paddingleft); // virtual screen
         let mut last_cursor = (
-            self.content_height + self.extray - (self.style.border * 2) - 1, // do not consider the borderwidth in the lastcursor of this window
-            self.content_width + self.extrax - (self.style.border * 2) - 1,
+            self.content_height + self.extray - self.style.frame_y() - 1, // do not consider the borderwidth in the lastcursor of this window
+            self.content_width + self.extrax - self.style.frame_x() - 1,
         );
         // do not consider the padding along the direction
 
@@ -1406,7 +2154,12 @@ impl IView {
         }
 
         // LOGLn!("{:p} {:?}", self, event);
+        if event.locating {
+            self.fill_target_info(event);
+            return self.transfer_event(event);
+        }
         // handle capture
+        self.fill_scroll_info(event);
         self.style.handle_event(event, true);
         if !event.propogate {
             return Ok(());
@@ -1425,6 +2178,8 @@ impl IView {
 
         // handle bubble
         if event.propogate {
+            // offsets after the default scroll
+            self.fill_scroll_info(event);
             self.style.handle_event(event, false);
         }
         Ok(())
@@ -0,0 +1,135 @@
+/***
+ * Turns the codes given by getch into KeyEvents
+ */
+
+use ncurses::{
+    keyname, KEY_BACKSPACE, KEY_BTAB, KEY_DC, KEY_DOWN, KEY_END, KEY_ENTER, KEY_F0, KEY_HOME, KEY_IC,
+    KEY_LEFT, KEY_MAX, KEY_MIN, KEY_NPAGE, KEY_PPAGE, KEY_RIGHT, KEY_SDC, KEY_SEND, KEY_SF, KEY_SHOME, KEY_SLEFT,
+    KEY_SNEXT, KEY_SPREVIOUS, KEY_SR, KEY_SRIGHT, KEY_UP,
+};
+
+use crate::{
+    interfaces::{Key, KeyEvent, Modifiers},
+    styles::{KEY_CTRL_END, KEY_CTRL_HOME},
+};
+
+const ESC: i32 = 27;
+
+/** Highest function key ncurses gives a code to */
+const MAX_F: i32 = 63;
+
+fn plain(key: Key) -> KeyEvent {
+    KeyEvent::new(key, Modifiers::NONE)
+}
+
+/**
+ * Decodes the key whose first code is ch.
+ * next gives the codes which are already waiting, -1 if none. unget gives back a code read which is not part of this key.
+ * ESC followed by a character is that character with Alt. The bytes of a UTF-8 character are joined.
+ */
+pub(crate) fn decode_key(
+    ch: i32,
+    next: &mut impl FnMut() -> i32,
+    unget: &mut impl FnMut(i32),
+) -> KeyEvent {
+    match ch {
+        ESC => match next() {
+            -1 => plain(Key::Esc),
+            // Eg: KEY_MOUSE or KEY_RESIZE, an event of its own
+            following if following >= KEY_MIN => {
+                unget(following);
+                plain(Key::Esc)
+            }
+            following => {
+                let event = decode_key(following, next, unget);
+                KeyEvent::new(event.key, event.modifiers | Modifiers::ALT)
+            }
+        },
+        9 => plain(Key::Tab),
+        10 | 13 => plain(Key::Enter),
+        8 | 127 => plain(Key::Backspace),
+        0 => KeyEvent::new(Key::Char(' '), Modifiers::CTRL),
+        // Ctrl + a..z, except the ones above
+        1..=26 => KeyEvent::new(Key::Char((b'a' + ch as u8 - 1) as char), Modifiers::CTRL),
+        // Ctrl + \ ] ^ _
+        28..=31 => KeyEvent::new(Key::Char((b'\\' + ch as u8 - 28) as char), Modifiers::CTRL),
+        32..=126 => plain(Key::Char(ch as u8 as char)),
+        0xC0..=0xF7 => decode_utf8(ch, next),
+        KEY_UP => plain(Key::Up),
+        KEY_DOWN => plain(Key::Down),
+        KEY_LEFT => plain(Key::Left),
+        KEY_RIGHT => plain(Key::Right),
+        KEY_HOME => plain(Key::Home),
+        KEY_END => plain(Key::End),
+        KEY_PPAGE => plain(Key::PageUp),
+        KEY_NPAGE => plain(Key::PageDown),
+        KEY_IC => plain(Key::Insert),
+        KEY_DC => plain(Key::Delete),
+        KEY_BACKSPACE => plain(Key::Backspace),
+        KEY_ENTER => plain(Key::Enter),
+        KEY_BTAB => KeyEvent::new(Key::Tab, Modifiers::SHIFT),
+        KEY_SR => KeyEvent::new(Key::Up, Modifiers::SHIFT),
+        KEY_SF => KeyEvent::new(Key::Down, Modifiers::SHIFT),
+        KEY_SLEFT => KeyEvent::new(Key::Left, Modifiers::SHIFT),
+        KEY_SRIGHT => KeyEvent::new(Key::Right, Modifiers::SHIFT),
+        KEY_SHOME => KeyEvent::new(Key::Home, Modifiers::SHIFT),
+        KEY_SEND => KeyEvent::new(Key::End, Modifiers::SHIFT),
+        KEY_SPREVIOUS => KeyEvent::new(Key::PageUp, Modifiers::SHIFT),
+        KEY_SNEXT => KeyEvent::new(Key::PageDown, Modifiers::SHIFT),
+        KEY_SDC => KeyEvent::new(Key::Delete, Modifiers::SHIFT),
+        KEY_CTRL_HOME => KeyEvent::new(Key::Home, Modifiers::CTRL),
+        KEY_CTRL_END => KeyEvent::new(Key::End, Modifiers::CTRL),
+        _ if ch > KEY_F0 && ch <= KEY_F0 + MAX_F => plain(Key::F((ch - KEY_F0) as u8)),
+        // keys ncurses creates from the terminfo extended capabilities. Eg: kUP5 for Ctrl + Up
+        _ if ch > KEY_MAX => keyname(ch)
+            .and_then(|name| decode_key_name(&name))
+            .unwrap_or(plain(Key::Unknown(ch))),
+        _ => plain(Key::Unknown(ch)),
+    }
+}
+
+/** Joins the continuation bytes of the UTF-8 character starting with lead */
+fn decode_utf8(lead: i32, next: &mut impl FnMut() -> i32) -> KeyEvent {
+    let len = match lead {
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        _ => 4,
+    };
+    let mut bytes = vec![lead as u8];
+    for _ in 1..len {
+        match next() {
+            byte @ 0x80..=0xBF => bytes.push(byte as u8),
+            _ => return plain(Key::Unknown(lead)),
+        }
+    }
+    match std::str::from_utf8(&bytes).ok().and_then(|s| s.chars().next()) {
+        Some(c) => plain(Key::Char(c)),
+        None => plain(Key::Unknown(lead)),
+    }
+}
+
+/**
+ * Decodes the name of an extended key. Eg: "kUP5", "kDC3".
+ * The number is xterm's modifier parameter: 1 + (Shift 1, Alt 2, Ctrl 4)
+ */
+pub(crate) fn decode_key_name(name: &str) -> Option<KeyEvent> {
+    let base = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let parameter: u8 = name[base.len()..].parse().ok()?;
+    let key = match base {
+        "kUP" => Key::Up,
+        "kDN" => Key::Down,
+        "kLFT" => Key::Left,
+        "kRIT" => Key::Right,
+        "kHOM" => Key::Home,
+        "kEND" => Key::End,
+        "kPRV" => Key::PageUp,
+        "kNXT" => Key::PageDown,
+        "kIC" => Key::Insert,
+        "kDC" => Key::Delete,
+        _ => return None,
+    };
+    if !(2..=8).contains(&parameter) {
+        return None;
+    }
+    Some(KeyEvent::new(key, Modifiers(parameter - 1)))
+}
@@ -1 +1,3 @@
-pub mod iview;
\ No newline at end of file
+pub mod iview;
+pub mod textlayout;
+pub mod keys;
\ No newline at end of file
@@ -0,0 +1,236 @@
+/***
+ * Breaks the text of a Text into lines
+ */
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::styles::{TEXTALIGN, WHITESPACE};
+
+/**
+ * A grapheme cluster along with the index of the span it belongs to. Plain Text uses 0.
+ * A cluster is never split, hence emoji and combining marks stay together.
+ */
+pub(crate) type Cell = (String, usize);
+
+pub(crate) const ELLIPSIS: &str = "…";
+
+/** Spaces a tab is expanded into */
+const TAB_SIZE: usize = 4;
+
+#[derive(Default)]
+pub(crate) struct TextLine {
+    pub(crate) cells: Vec<Cell>,
+    /** Last line of a paragraph i.e. followed by a newline or the end of the text. Not justified */
+    pub(crate) last: bool,
+}
+
+impl TextLine {
+    pub(crate) fn width(&self) -> i32 {
+        cells_width(&self.cells)
+    }
+}
+
+/** Number of terminal columns taken by the grapheme. CJK and most emoji take 2 */
+pub(crate) fn grapheme_width(grapheme: &str) -> i32 {
+    grapheme.width() as i32
+}
+
+/** Number of terminal columns taken by the cells */
+pub(crate) fn cells_width(cells: &[Cell]) -> i32 {
+    cells.iter().map(|(g, _)| grapheme_width(g)).sum()
+}
+
+fn is_space(cell: &Cell) -> bool {
+    cell.0 == " "
+}
+
+/** returns the cells of the text, taking the span index from the spans if any */
+pub(crate) fn text_cells<'a>(txt: &str, spans: impl Iterator<Item = &'a str>) -> Vec<Cell> {
+    let cells: Vec<Cell> = spans
+        .enumerate()
+        .flat_map(|(idx, text)| text.graphemes(true).map(move |g| (g.to_string(), idx)))
+        .collect();
+    if cells.is_empty() {
+        txt.graphemes(true).map(|g| (g.to_string(), 0)).collect()
+    } else {
+        cells
+    }
+}
+
+/**
+ * Splits the cells into paragraphs according to white space.
+ * WRAP collapses all the whitespaces (including newlines) into a single space.
+ */
+fn paragraphs(cells: Vec<Cell>, white_space: WHITESPACE) -> Vec<Vec<Cell>> {
+    match white_space {
+        WHITESPACE::WRAP => {
+            let mut paragraph: Vec<Cell> = vec![];
+            for (g, idx) in cells {
+                if g.chars().all(char::is_whitespace) {
+                    if paragraph.last().is_some_and(|last| !is_space(last)) {
+                        paragraph.push((" ".to_string(), idx));
+                    }
+                } else {
+                    paragraph.push((g, idx));
+                }
+            }
+            if paragraph.last().is_some_and(is_space) {
+                paragraph.pop();
+            }
+            vec![paragraph]
+        }
+        WHITESPACE::NOWRAP | WHITESPACE::PREWRAP => {
+            let mut paragraphs = vec![vec![]];
+            for (g, idx) in cells {
+                match g.as_str() {
+                    "\n" | "\r\n" => paragraphs.push(vec![]),
+                    "\t" => paragraphs
+                        .last_mut()
+                        .unwrap()
+                        .extend(std::iter::repeat_n((" ".to_string(), idx), TAB_SIZE)),
+                    "\r" => {}
+                    _ => paragraphs.last_mut().unwrap().push((g, idx)),
+                }
+            }
+            paragraphs
+        }
+    }
+}
+
+/** Breaks a paragraph at word boundaries. Words longer than the width are broken */
+fn wrap_paragraph(paragraph: Vec<Cell>, width: i32, lines: &mut Vec<TextLine>) {
+    let mut line: Vec<Cell> = vec![];
+    let mut wrapped = false;
+
+    // alternate runs of spaces and words
+    let mut tokens: Vec<Vec<Cell>> = vec![];
+    for cell in paragraph {
+        match tokens.last_mut() {
+            Some(token) if is_space(&token[0]) == is_space(&cell) => token.push(cell),
+            _ => tokens.push(vec![cell]),
+        }
+    }
+
+    for mut token in tokens {
+        let token_width = cells_width(&token);
+        if is_space(&token[0]) {
+            if line.is_empty() && wrapped {
+                // spaces at the start of a wrapped line are dropped
+                continue;
+            }
+            if cells_width(&line) + token_width <= width {
+                line.extend(token);
+            } else {
+                lines.push(TextLine { cells: line, last: false });
+                line = vec![];
+                wrapped = true;
+            }
+            continue;
+        }
+
+        if cells_width(&line) + token_width > width && !line.is_empty() {
+            while line.last().is_some_and(is_space) {
+                line.pop();
+            }
+            lines.push(TextLine { cells: line, last: false });
+            line = vec![];
+            wrapped = true;
+        }
+        while cells_width(&token) > width {
+            // as many clusters as fit, but at least one so that a wide cluster in a narrow width moves ahead
+            let mut fit = 0;
+            let mut fit_width = 0;
+            while fit < token.len() && fit_width + grapheme_width(&token[fit].0) <= width {
+                fit_width += grapheme_width(&token[fit].0);
+                fit += 1;
+            }
+            let rest = token.split_off(fit.max(1));
+            lines.push(TextLine { cells: token, last: false });
+            token = rest;
+            wrapped = true;
+        }
+        line.extend(token);
+    }
+    lines.push(TextLine { cells: line, last: true });
+}
+
+/**
+ * Breaks the text into lines.
+ * width <= 0 means there is no limit on the width.
+ */
+pub(crate) fn layout_text(cells: Vec<Cell>, width: i32, white_space: WHITESPACE) -> Vec<TextLine> {
+    let mut lines = vec![];
+    for paragraph in paragraphs(cells, white_space) {
+        if width <= 0 || matches!(white_space, WHITESPACE::NOWRAP) {
+            lines.push(TextLine { cells: paragraph, last: true });
+        } else {
+            wrap_paragraph(paragraph, width, &mut lines);
+        }
+    }
+    lines
+}
+
+/** Cuts the line to fit in width along with the ellipsis */
+fn add_ellipsis(line: &mut TextLine, width: i32) {
+    let idx = line.cells.last().map(|(_, idx)| *idx).unwrap_or(0);
+    let ellipsis_width = grapheme_width(ELLIPSIS);
+    while !line.cells.is_empty() && line.width() + ellipsis_width > width {
+        line.cells.pop();
+    }
+    if width >= ellipsis_width {
+        line.cells.push((ELLIPSIS.to_string(), idx));
+    }
+}
+
+/**
+ * Ends the lines which do not fit in width with an ellipsis.
+ * If there are more lines than height, the last visible line ends with an ellipsis.
+ */
+pub(crate) fn truncate_lines(lines: &mut Vec<TextLine>, width: i32, height: i32) {
+    lines
+        .iter_mut()
+        .filter(|line| line.width() > width)
+        .for_each(|line| add_ellipsis(line, width));
+
+    if height >= 0 && lines.len() > height as usize {
+        lines.truncate(height as usize);
+        if let Some(line) = lines.last_mut() {
+            add_ellipsis(line, width);
+            line.last = true;
+        }
+    }
+}
+
+/** returns the column of each cell of the line after aligning it in the width */
+pub(crate) fn line_positions(line: &TextLine, width: i32, align: TEXTALIGN) -> Vec<i32> {
+    let free = (width - line.width()).max(0);
+    let mut x = match align {
+        TEXTALIGN::LEFT | TEXTALIGN::JUSTIFY => 0,
+        TEXTALIGN::CENTER => free / 2,
+        TEXTALIGN::RIGHT => free,
+    };
+
+    // gaps between the words get the free space
+    let gaps = line
+        .cells
+        .windows(2)
+        .filter(|pair| is_space(&pair[0]) && !is_space(&pair[1]))
+        .count() as i32;
+    let justify = matches!(align, TEXTALIGN::JUSTIFY) && !line.last && gaps > 0;
+
+    let mut gap = 0;
+    line.cells
+        .iter()
+        .enumerate()
+        .map(|(i, cell)| {
+            let pos = x;
+            x += grapheme_width(&cell.0);
+            if justify && is_space(cell) && line.cells.get(i + 1).is_some_and(|next| !is_space(next)) {
+                x += free / gaps + if gap < free % gaps { 1 } else { 0 };
+                gap += 1;
+            }
+            pos
+        })
+        .collect()
+}
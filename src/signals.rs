@@ -0,0 +1,58 @@
+/***
+ * Signal handlers only note the signal. It is acted upon in the main loop
+ */
+
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use libc::{SIG_DFL, SIGCONT, SIGINT, SIGTERM, SIGTSTP, c_int, raise, sighandler_t, signal};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SIGNAL {
+    /** Ctrl-Z or SIGTSTP. By default the terminal is restored and the app stops until it is continued */
+    SUSPEND,
+    /** SIGCONT, the app continued after being stopped. By default the screen is drawn again */
+    RESUME,
+    /** Ctrl-C or SIGINT. By default the app exits */
+    INTERRUPT,
+    /** SIGTERM. By default the app exits */
+    TERMINATE,
+}
+
+/** bit (1 << signum) for each signal received and not yet handled */
+static PENDING: AtomicI32 = AtomicI32::new(0);
+
+const SIGNALS: [(c_int, SIGNAL); 4] = [
+    (SIGTSTP, SIGNAL::SUSPEND),
+    (SIGCONT, SIGNAL::RESUME),
+    (SIGINT, SIGNAL::INTERRUPT),
+    (SIGTERM, SIGNAL::TERMINATE),
+];
+
+extern "C" fn note_signal(signum: c_int) {
+    PENDING.fetch_or(1 << signum, Ordering::SeqCst);
+}
+
+pub(crate) fn install_handlers() {
+    SIGNALS.iter().for_each(|(signum, _)| unsafe {
+        signal(*signum, note_signal as extern "C" fn(c_int) as sighandler_t);
+    });
+}
+
+/** Signals received since the last call */
+pub(crate) fn take_pending() -> Vec<SIGNAL> {
+    let pending = PENDING.swap(0, Ordering::SeqCst);
+    SIGNALS
+        .iter()
+        .filter(|(signum, _)| pending & (1 << signum) != 0)
+        .map(|(_, received)| *received)
+        .collect()
+}
+
+/** Stops the process the way SIGTSTP does by default. Returns once it is continued */
+pub(crate) fn stop() {
+    unsafe {
+        signal(SIGTSTP, SIG_DFL);
+        raise(SIGTSTP);
+        signal(SIGTSTP, note_signal as extern "C" fn(c_int) as sighandler_t);
+    }
+}
@@ -4,23 +4,59 @@ use std::{
 };
 
 use ncurses::{
-    endwin, BUTTON1_PRESSED, BUTTON3_PRESSED, BUTTON4_PRESSED, BUTTON5_PRESSED, KEY_ENTER
+    attr_t, endwin, A_BLINK, A_BOLD, A_DIM, A_ITALIC, A_NORMAL, A_REVERSE, A_UNDERLINE, BUTTON1_PRESSED, BUTTON1_RELEASED, BUTTON2_PRESSED, BUTTON2_RELEASED, BUTTON3_PRESSED, BUTTON3_RELEASED, BUTTON4_PRESSED, BUTTON5_PRESSED, REPORT_MOUSE_POSITION
 };
 
-use crate::{interfaces::EVENT, LOGLn};
+use crate::{components::richtext::Span, interfaces::{Document, EVENT}, theme::Theme};
+use unicode_segmentation::UnicodeSegmentation;
 
 pub const TRANSPARENT:i16 = -2;
 
+/** Text attributes. Not given attributes are inherited from the parent */
+pub const BOLD: u32 = 1;
+pub const ITALIC: u32 = 1 << 1;
+pub const UNDERLINE: u32 = 1 << 2;
+pub const REVERSE: u32 = 1 << 3;
+pub const DIM: u32 = 1 << 4;
+pub const BLINK: u32 = 1 << 5;
+/** ncurses has no attribute for this, hence drawn using a combining long stroke(U+0336) after each character */
+pub const STRIKETHROUGH: u32 = 1 << 6;
+
+/** Underline and italic on a border line does not look like a border anymore */
+pub(crate) const BORDER_ATTRIBUTES: u32 = BOLD | REVERSE | DIM | BLINK;
+
+/** converts the text attributes to ncurses attributes */
+pub(crate) fn ncurses_attributes(attributes: u32) -> attr_t {
+    [
+        (BOLD, A_BOLD),
+        (ITALIC, A_ITALIC),
+        (UNDERLINE, A_UNDERLINE),
+        (REVERSE, A_REVERSE),
+        (DIM, A_DIM),
+        (BLINK, A_BLINK),
+    ]
+    .iter()
+    .filter(|(attribute, _)| attributes & attribute > 0)
+    .fold(A_NORMAL, |prev, (_, a)| prev | a)
+}
+
 pub struct CSSStyle<'a> {
     pub padding: &'a str,
     pub margin: &'a str,
-    pub background_color: i16,
-    pub color: i16,
+    pub background_color: &'a str,
+    pub color: &'a str,
     pub flex: u32,
     pub flex_wrap: bool,
     pub flex_direction: &'a str,
     pub taborder: i32,
-    pub border_color: i16,
+    pub border_color: &'a str,
+    /** "single", "double", "rounded", "heavy", "dashed", "ascii" or 6 characters: horizontal, vertical and the corners top-left, top-right, bottom-left, bottom-right. Eg: "-|++++" */
+    pub border_style: &'a str,
+    /** "width [color]". Eg: "1", "1 red" or "0". Overrides `border` for the top side */
+    pub border_top: &'a str,
+    pub border_right: &'a str,
+    pub border_bottom: &'a str,
+    pub border_left: &'a str,
     pub position: &'a str,
     pub boxsizing: &'a str,
     pub border: i32,
@@ -30,8 +66,38 @@ pub struct CSSStyle<'a> {
     pub width: &'a str,
     pub overflow: &'a str,
     pub z_index: i32,
+    /** "normal", "bold" or "dim" */
+    pub font_weight: &'a str,
+    /** "normal" or "italic" */
+    pub font_style: &'a str,
+    /** space separated: "underline", "line-through", "blink", "reverse" or "none" */
+    pub text_decoration: &'a str,
+    /** "wrap", "nowrap" or "pre-wrap" */
+    pub white_space: &'a str,
+    /** "left", "center", "right" or "justify" */
+    pub text_align: &'a str,
+    /** "clip" or "ellipsis" */
+    pub text_overflow: &'a str,
+    /** "flex" or "none". "none" hides the element, taking no space */
+    pub display: &'a str,
+    /** "none", "vertical", "horizontal" or "both". Only when overflow is "scroll" */
+    pub scrollbar: &'a str,
     /** If multiple element has this value set then only the last element actually uses this property */
     pub flex_grow: bool,
+    /** Keeps a scrollable element scrolled to the bottom as its content grows, unless user scrolled up */
+    pub stick_to_bottom: bool,
+    /** Rows (or columns) scrolled by one step of the mouse wheel */
+    pub wheel_step: i32,
+    /** Name of the focus group. Arrow keys move the focus among the elements of the focused element's group */
+    pub focus_group: &'a str,
+    /** Applied over this style while the element is focused. Only the fields which differ from default are applied */
+    pub focus: Option<&'a CSSStyle<'a>>,
+    /** Applied over this style while the mouse is over the element */
+    pub hover: Option<&'a CSSStyle<'a>>,
+    /** Applied over this style while the element is pressed by the mouse */
+    pub active: Option<&'a CSSStyle<'a>>,
+    /** Applied over this style while the terminal size is in the range of the Media. Pseudo-class styles are applied after these */
+    pub media: &'a [(Media, CSSStyle<'a>)],
 }
 
 impl<'a> Default for CSSStyle<'a> {
@@ -42,12 +108,17 @@ impl<'a> Default for CSSStyle<'a> {
             flex_direction: Default::default(),
             position: Default::default(),
             boxsizing: Default::default(),
-            background_color: TRANSPARENT,
-            color: -1,
+            background_color: Default::default(),
+            color: Default::default(),
             flex_wrap: false,
             flex: 0,
             taborder: -1,
-            border_color: TRANSPARENT,
+            border_color: Default::default(),
+            border_style: Default::default(),
+            border_top: Default::default(),
+            border_right: Default::default(),
+            border_bottom: Default::default(),
+            border_left: Default::default(),
             border: 0,
             top: Default::default(),
             left: Default::default(),
@@ -55,7 +126,22 @@ impl<'a> Default for CSSStyle<'a> {
             width: Default::default(),
             overflow: Default::default(),
             z_index: 0,
+            font_weight: Default::default(),
+            font_style: Default::default(),
+            text_decoration: Default::default(),
+            white_space: Default::default(),
+            text_align: Default::default(),
+            text_overflow: Default::default(),
+            display: Default::default(),
+            scrollbar: Default::default(),
             flex_grow: false,
+            stick_to_bottom: false,
+            wheel_step: 1,
+            focus_group: Default::default(),
+            focus: None,
+            hover: None,
+            active: None,
+            media: &[],
         }
     }
 }
@@ -86,6 +172,14 @@ fn parse_multi_dimens<'a>(mut d: &'a str) -> [DIMEN; 4] {
         .unwrap_or_else(|v: Vec<DIMEN>| panic!("Expected 4 dimens: {}", v.len()))
 }
 
+/** STYLE expects percentage between 0 - 100, hence converts it back */
+fn to_style_dimen(d: DIMEN) -> DIMEN {
+    match d {
+        DIMEN::PERCENT(p) => DIMEN::PERCENT(p * 100.),
+        DIMEN::INT(i) => DIMEN::INT(i),
+    }
+}
+
 fn parse_flex_direction<'a>(d: &'a str) -> FLEXDIRECTION {
     match d.trim() {
         "vertical" => FLEXDIRECTION::VERTICAL,
@@ -95,6 +189,126 @@ fn parse_flex_direction<'a>(d: &'a str) -> FLEXDIRECTION {
         }
     }
 }
+/** names which map to the terminal's own colors */
+const NAMED_COLORS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/** names which are not in the standard colors, created using `Document::new_color` */
+const NAMED_RGB_COLORS: [(&str, (i16, i16, i16)); 10] = [
+    ("orange", (255, 165, 0)),
+    ("purple", (128, 0, 128)),
+    ("pink", (255, 192, 203)),
+    ("brown", (165, 42, 42)),
+    ("navy", (0, 0, 128)),
+    ("teal", (0, 128, 128)),
+    ("olive", (128, 128, 0)),
+    ("maroon", (128, 0, 0)),
+    ("lime", (50, 205, 50)),
+    ("gold", (255, 215, 0)),
+];
+
+/**
+ * Parses the color. Accepted formats:
+ * palette index: "1", "-1"
+ * "transparent", "default"
+ * theme roles: "primary", "on-surface"
+ * names: "red", "bright-red", "grey", "orange"
+ * hex: "#ff8800", "#f80"
+ * rgb: "rgb(255, 136, 0)"
+ */
+pub(crate) fn parse_color(d: &str) -> i16 {
+    let d = d.trim().to_lowercase();
+    if let Ok(index) = d.parse::<i16>() {
+        return index;
+    }
+    if let Some(role) = Theme::role(&d) {
+        return role;
+    }
+    if let Some(hex) = d.strip_prefix('#') {
+        // an invalid hex keeps the terminal's color
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return -1;
+        }
+        let component = |s: &str| i16::from_str_radix(s, 16).unwrap_or(0);
+        let rgb = match hex.len() {
+            6 => (component(&hex[0..2]), component(&hex[2..4]), component(&hex[4..6])),
+            // #f80 is same as #ff8800
+            3 => (
+                component(&hex[0..1]) * 17,
+                component(&hex[1..2]) * 17,
+                component(&hex[2..3]) * 17,
+            ),
+            _ => return -1,
+        };
+        return Document::new_color(rgb.0, rgb.1, rgb.2);
+    }
+    if let Some(rgb) = d.strip_prefix("rgb(").and_then(|d| d.strip_suffix(')')) {
+        let [r, g, b]: [i16; 3] = rgb
+            .split(',')
+            .map(|c| c.trim().parse::<i16>().expect("Invalid Color"))
+            .collect::<Vec<i16>>()
+            .try_into()
+            .unwrap_or_else(|v: Vec<i16>| panic!("Expected 3 color components: {}", v.len()));
+        return Document::new_color(r, g, b);
+    }
+    match d.as_str() {
+        "transparent" => return TRANSPARENT,
+        "default" => return -1,
+        "grey" | "gray" => return 8,
+        _ => {}
+    }
+    if let Some(idx) = NAMED_COLORS.iter().position(|name| *name == d) {
+        return idx as i16;
+    }
+    if let Some(idx) = d
+        .strip_prefix("bright-")
+        .and_then(|d| NAMED_COLORS.iter().position(|name| *name == d))
+    {
+        return 8 + idx as i16;
+    }
+    if let Some((_, rgb)) = NAMED_RGB_COLORS.iter().find(|(name, _)| *name == d) {
+        return Document::new_color(rgb.0, rgb.1, rgb.2);
+    }
+    panic!("Invalid Color")
+}
+
+fn parse_font_weight(d: &str) -> u32 {
+    match d.trim() {
+        "normal" => 0,
+        "bold" => BOLD,
+        "dim" => DIM,
+        _ => {
+            panic!("Invalid Font Weight")
+        }
+    }
+}
+
+fn parse_font_style(d: &str) -> u32 {
+    match d.trim() {
+        "normal" => 0,
+        "italic" => ITALIC,
+        _ => {
+            panic!("Invalid Font Style")
+        }
+    }
+}
+
+fn parse_text_decoration(d: &str) -> u32 {
+    d.split_whitespace().fold(0, |prev, decoration| {
+        prev | match decoration {
+            "none" => 0,
+            "underline" => UNDERLINE,
+            "line-through" => STRIKETHROUGH,
+            "blink" => BLINK,
+            "reverse" => REVERSE,
+            _ => {
+                panic!("Invalid Text Decoration")
+            }
+        }
+    })
+}
+
 fn parse_position<'a>(d: &'a str) -> POSITION {
     match d.trim() {
         "static" => POSITION::STATIC,
@@ -116,6 +330,103 @@ fn parse_overflow<'a>(d: &'a str) -> OVERFLOWBEHAVIOUR {
     }
 }
 
+fn parse_display(d: &str) -> DISPLAY {
+    match d.trim() {
+        "flex" => DISPLAY::FLEX,
+        "none" => DISPLAY::NONE,
+        _ => {
+            panic!("Invalid Display")
+        }
+    }
+}
+
+fn parse_white_space(d: &str) -> WHITESPACE {
+    match d.trim() {
+        "wrap" | "normal" => WHITESPACE::WRAP,
+        "nowrap" => WHITESPACE::NOWRAP,
+        "pre-wrap" => WHITESPACE::PREWRAP,
+        _ => {
+            panic!("Invalid White Space")
+        }
+    }
+}
+
+fn parse_text_align(d: &str) -> TEXTALIGN {
+    match d.trim() {
+        "left" => TEXTALIGN::LEFT,
+        "center" => TEXTALIGN::CENTER,
+        "right" => TEXTALIGN::RIGHT,
+        "justify" => TEXTALIGN::JUSTIFY,
+        _ => {
+            panic!("Invalid Text Align")
+        }
+    }
+}
+
+fn parse_text_overflow(d: &str) -> TEXTOVERFLOW {
+    match d.trim() {
+        "clip" => TEXTOVERFLOW::CLIP,
+        "ellipsis" => TEXTOVERFLOW::ELLIPSIS,
+        _ => {
+            panic!("Invalid Text Overflow")
+        }
+    }
+}
+
+fn parse_border_style(d: &str) -> BORDERSTYLE {
+    match d.trim() {
+        "single" => BORDERSTYLE::SINGLE,
+        "double" => BORDERSTYLE::DOUBLE,
+        "rounded" => BORDERSTYLE::ROUNDED,
+        "heavy" => BORDERSTYLE::HEAVY,
+        "dashed" => BORDERSTYLE::DASHED,
+        "ascii" => BORDERSTYLE::ASCII,
+        custom => {
+            let chars: Vec<String> = custom.graphemes(true).map(|g| g.to_string()).collect();
+            let Ok([horizontal, vertical, top_left, top_right, bottom_left, bottom_right]) =
+                <[String; 6]>::try_from(chars)
+            else {
+                panic!("Invalid Border Style")
+            };
+            BORDERSTYLE::CUSTOM(BorderChars {
+                horizontal,
+                vertical,
+                top_left,
+                top_right,
+                bottom_left,
+                bottom_right,
+            })
+        }
+    }
+}
+
+/** parses "width [color]" of a border side */
+fn parse_border_side(d: &str) -> (bool, Option<i16>) {
+    let d = d.trim();
+    let (width, color) = d.split_once(' ').unwrap_or((d, ""));
+    let width = width
+        .parse::<i32>()
+        .unwrap_or_else(|_| panic!("Invalid Border Width: {}", width));
+    let color = if color.trim().is_empty() {
+        None
+    } else {
+        Some(parse_color(color))
+    };
+    (width > 0, color)
+}
+
+fn parse_scrollbar(d: &str) -> SCROLLBAR {
+    match d.trim() {
+        "none" => SCROLLBAR::NONE,
+        "vertical" => SCROLLBAR::VERTICAL,
+        "horizontal" => SCROLLBAR::HORIZONTAL,
+        "both" => SCROLLBAR::BOTH,
+        _ => {
+            panic!("Invalid Scrollbar")
+        }
+    }
+}
+
 fn parse_box_sizing<'a>(d: &'a str) -> BOXSIZING {
     match d.trim() {
         "border-box" => BOXSIZING::BORDERBOX,
@@ -151,12 +462,29 @@ impl<'a> CSSStyle<'a> {
         }
         style.z_index = self.z_index;
         style.flex_wrap = self.flex_wrap;
-        style.background_color = self.background_color;
-        style.border = self.border;
-        style.border_color = self.border_color;
-        style.color = self.color;
+        if !self.background_color.is_empty() {
+            style.background_color = parse_color(self.background_color);
+        }
+        style.set_style(STYLE::BORDER(self.border > 0));
+        if !self.border_style.is_empty() {
+            style.border_style = parse_border_style(self.border_style);
+        }
+        self.border_side_styles()
+            .into_iter()
+            .for_each(|v| style.set_style(v));
+        style.border_color = if self.border_color.is_empty() {
+            TRANSPARENT
+        } else {
+            parse_color(self.border_color)
+        };
+        if !self.color.is_empty() {
+            style.color = parse_color(self.color);
+        }
         style.flex = self.flex;
         style.flex_grow = self.flex_grow;
+        style.stick_to_bottom = self.stick_to_bottom;
+        style.wheel_step = self.wheel_step;
+        style.focus_group = self.focus_group.to_string();
         style.taborder = self.taborder;
         if !self.flex_direction.is_empty() {
             style.flex_direction = parse_flex_direction(self.flex_direction);
@@ -176,8 +504,191 @@ impl<'a> CSSStyle<'a> {
         if !self.left.is_empty() {
             style.left = parse_dimension(self.left);
         }
+        if !self.white_space.is_empty() {
+            style.white_space = parse_white_space(self.white_space);
+        }
+        if !self.text_align.is_empty() {
+            style.text_align = parse_text_align(self.text_align);
+        }
+        if !self.text_overflow.is_empty() {
+            style.text_overflow = parse_text_overflow(self.text_overflow);
+        }
+        if !self.display.is_empty() {
+            style.display = parse_display(self.display);
+        }
+        if !self.scrollbar.is_empty() {
+            style.scrollbar = parse_scrollbar(self.scrollbar);
+        }
+        self.attribute_styles()
+            .into_iter()
+            .for_each(|v| style.set_style(v));
+        if let Some(focus) = self.focus {
+            style.focus_style = focus.create_style_overrides();
+        }
+        if let Some(hover) = self.hover {
+            style.hover_style = hover.create_style_overrides();
+        }
+        if let Some(active) = self.active {
+            style.active_style = active.create_style_overrides();
+        }
+        style.media_styles = self
+            .media
+            .iter()
+            .map(|(media, media_style)| (*media, media_style.create_style_overrides()))
+            .collect();
         style
     }
+
+    /**
+     * Converts only those fields which differ from the default into STYLE.
+     * Used by pseudo-class styles so that they override only what is given.
+     */
+    pub(crate) fn create_style_overrides(&self) -> Vec<STYLE> {
+        let default = CSSStyle::default();
+        let mut styles = vec![];
+        if !self.height.is_empty() {
+            styles.push(STYLE::HIEGHT(to_style_dimen(parse_dimension(self.height))));
+        }
+        if !self.width.is_empty() {
+            styles.push(STYLE::WIDTH(to_style_dimen(parse_dimension(self.width))));
+        }
+        if !self.padding.is_empty() {
+            let [top, bottom, left, right] = parse_multi_dimens(self.padding).map(to_style_dimen);
+            styles.push(STYLE::PADDINGTOP(top));
+            styles.push(STYLE::PADDINGBOTTOM(bottom));
+            styles.push(STYLE::PADDINGLEFT(left));
+            styles.push(STYLE::PADDINGRIGHT(right));
+        }
+        if !self.margin.is_empty() {
+            let [top, bottom, left, right] = parse_multi_dimens(self.margin).map(to_style_dimen);
+            styles.push(STYLE::MARGINTOP(top));
+            styles.push(STYLE::MARGINBOTTOM(bottom));
+            styles.push(STYLE::MARGINLEFT(left));
+            styles.push(STYLE::MARGINRIGHT(right));
+        }
+        if !self.top.is_empty() {
+            styles.push(STYLE::TOP(to_style_dimen(parse_dimension(self.top))));
+        }
+        if !self.left.is_empty() {
+            styles.push(STYLE::LEFT(to_style_dimen(parse_dimension(self.left))));
+        }
+        if self.z_index != default.z_index {
+            styles.push(STYLE::ZINDEX(self.z_index));
+        }
+        if self.flex_wrap != default.flex_wrap {
+            styles.push(STYLE::FLEXWRAP(self.flex_wrap));
+        }
+        if !self.background_color.is_empty() {
+            styles.push(STYLE::BACKGROUNDCOLOR(parse_color(self.background_color)));
+        }
+        if self.border != default.border {
+            styles.push(STYLE::BORDER(self.border > 0));
+        }
+        if !self.border_color.is_empty() {
+            styles.push(STYLE::BORDERCOLOR(parse_color(self.border_color)));
+        }
+        if !self.border_style.is_empty() {
+            styles.push(STYLE::BORDERSTYLE(parse_border_style(self.border_style)));
+        }
+        styles.extend(self.border_side_styles());
+        if !self.color.is_empty() {
+            styles.push(STYLE::TEXTCOLOR(parse_color(self.color)));
+        }
+        if self.flex != default.flex {
+            styles.push(STYLE::FLEX(self.flex));
+        }
+        if self.flex_grow != default.flex_grow {
+            styles.push(STYLE::FLEXGROW(self.flex_grow));
+        }
+        if self.stick_to_bottom != default.stick_to_bottom {
+            styles.push(STYLE::STICKTOBOTTOM(self.stick_to_bottom));
+        }
+        if self.wheel_step != default.wheel_step {
+            styles.push(STYLE::WHEELSTEP(self.wheel_step));
+        }
+        if self.taborder != default.taborder {
+            styles.push(STYLE::TABORDER(self.taborder));
+        }
+        if !self.focus_group.is_empty() {
+            styles.push(STYLE::FOCUSGROUP(self.focus_group.to_string()));
+        }
+        if !self.flex_direction.is_empty() {
+            styles.push(STYLE::FLEXDIRECTION(parse_flex_direction(self.flex_direction)));
+        }
+        if !self.position.is_empty() {
+            styles.push(STYLE::POSITION(parse_position(self.position)));
+        }
+        if !self.boxsizing.is_empty() {
+            styles.push(STYLE::BOXSIZING(parse_box_sizing(self.boxsizing)));
+        }
+        if !self.overflow.is_empty() {
+            styles.push(STYLE::OVERFLOW(parse_overflow(self.overflow)));
+        }
+        if !self.white_space.is_empty() {
+            styles.push(STYLE::WHITESPACE(parse_white_space(self.white_space)));
+        }
+        if !self.text_align.is_empty() {
+            styles.push(STYLE::TEXTALIGN(parse_text_align(self.text_align)));
+        }
+        if !self.text_overflow.is_empty() {
+            styles.push(STYLE::TEXTOVERFLOW(parse_text_overflow(self.text_overflow)));
+        }
+        if !self.display.is_empty() {
+            styles.push(STYLE::DISPLAY(parse_display(self.display)));
+        }
+        if !self.scrollbar.is_empty() {
+            styles.push(STYLE::SCROLLBAR(parse_scrollbar(self.scrollbar)));
+        }
+        styles.extend(self.attribute_styles());
+        styles
+    }
+
+    /** border_top, border_right, border_bottom and border_left as STYLE. Only the given ones are returned */
+    fn border_side_styles(&self) -> Vec<STYLE> {
+        let mut styles = vec![];
+        let sides = [
+            (
+                self.border_top,
+                STYLE::BORDERTOP as fn(bool) -> STYLE,
+                STYLE::BORDERTOPCOLOR as fn(i16) -> STYLE,
+            ),
+            (self.border_right, STYLE::BORDERRIGHT, STYLE::BORDERRIGHTCOLOR),
+            (self.border_bottom, STYLE::BORDERBOTTOM, STYLE::BORDERBOTTOMCOLOR),
+            (self.border_left, STYLE::BORDERLEFT, STYLE::BORDERLEFTCOLOR),
+        ];
+        for (side, on_style, color_style) in sides {
+            if side.is_empty() {
+                continue;
+            }
+            let (on, color) = parse_border_side(side);
+            styles.push(on_style(on));
+            if let Some(color) = color {
+                styles.push(color_style(color));
+            }
+        }
+        styles
+    }
+
+    /** font_weight, font_style and text_decoration as STYLE. Only the given ones are returned */
+    fn attribute_styles(&self) -> Vec<STYLE> {
+        let mut styles = vec![];
+        if !self.font_weight.is_empty() {
+            let weight = parse_font_weight(self.font_weight);
+            styles.push(STYLE::BOLD(weight & BOLD > 0));
+            styles.push(STYLE::DIM(weight & DIM > 0));
+        }
+        if !self.font_style.is_empty() {
+            styles.push(STYLE::ITALIC(parse_font_style(self.font_style) & ITALIC > 0));
+        }
+        if !self.text_decoration.is_empty() {
+            let decoration = parse_text_decoration(self.text_decoration);
+            styles.push(STYLE::UNDERLINE(decoration & UNDERLINE > 0));
+            styles.push(STYLE::STRIKETHROUGH(decoration & STRIKETHROUGH > 0));
+            styles.push(STYLE::BLINK(decoration & BLINK > 0));
+            styles.push(STYLE::REVERSE(decoration & REVERSE > 0));
+        }
+        styles
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -214,6 +725,9 @@ impl DIMEN {
     }
 }
 
+/** An event handler given to an element */
+pub(crate) type Handler = Option<Arc<Mutex<dyn FnMut(&mut EVENT) + 'static>>>;
+
 #[derive(Default, Clone)]
 pub(crate) struct Style {
     pub(crate) height: DIMEN,
@@ -232,8 +746,25 @@ pub(crate) struct Style {
     pub(crate) flex: u32,
     pub(crate) flex_wrap: bool,
     pub(crate) flex_grow: bool,
-    pub(crate) border: i32,
+    pub(crate) stick_to_bottom: bool,
+    pub(crate) wheel_step: i32,
+    /** Empty means not in a focus group */
+    pub(crate) focus_group: String,
+    /** Width(0 or 1) of each side of the border */
+    pub(crate) border_top: i32,
+    pub(crate) border_right: i32,
+    pub(crate) border_bottom: i32,
+    pub(crate) border_left: i32,
     pub(crate) border_color: i16,
+    /** Color of each side. TRANSPARENT means border_color is used */
+    pub(crate) border_top_color: i16,
+    pub(crate) border_right_color: i16,
+    pub(crate) border_bottom_color: i16,
+    pub(crate) border_left_color: i16,
+    pub(crate) border_style: BORDERSTYLE,
+    /** Captions drawn over the top and bottom border. Boxed since Span itself holds a Style */
+    pub(crate) border_title: Option<Box<(Span, TEXTALIGN)>>,
+    pub(crate) border_footer: Option<Box<(Span, TEXTALIGN)>>,
     pub(crate) color: i16,
     pub(crate) background_color: i16,
     pub(crate) taborder: i32,
@@ -241,15 +772,53 @@ pub(crate) struct Style {
     pub(crate) boxsizing: BOXSIZING,
     pub(crate) flex_direction: FLEXDIRECTION,
     pub(crate) z_index: i32,
+    /** Text attributes given to this element */
+    pub(crate) attributes: u32,
+    /** Which of the text attributes are given. Rest are inherited from the parent */
+    pub(crate) attributes_set: u32,
     pub(crate) onclick_bubble: Option<Arc<Mutex<dyn FnMut(&mut EVENT) + 'static>>>, // should be a clousure
     pub(crate) onscroll_bubble: Option<Arc<Mutex<dyn FnMut(&mut EVENT) + 'static>>>, // should be a clousure
     pub(crate) onclick_capture: Option<Arc<Mutex<dyn FnMut(&mut EVENT) + 'static>>>, // should be a clousure
     pub(crate) onscroll_capture: Option<Arc<Mutex<dyn FnMut(&mut EVENT) + 'static>>>, // should be a clousure
+    pub(crate) onkeydown_bubble: Handler,
+    pub(crate) onkeydown_capture: Handler,
+    /** Called after the layout in which the size of the element changed, and after the first one */
+    pub(crate) onresize: Handler,
+    pub(crate) onpaste_bubble: Handler,
+    pub(crate) onpaste_capture: Handler,
+    pub(crate) onmousedown_bubble: Handler,
+    pub(crate) onmousedown_capture: Handler,
+    pub(crate) onmouseup_bubble: Handler,
+    pub(crate) onmouseup_capture: Handler,
+    pub(crate) oncontextmenu_bubble: Handler,
+    pub(crate) oncontextmenu_capture: Handler,
+    pub(crate) ondoubleclick_bubble: Handler,
+    pub(crate) ondoubleclick_capture: Handler,
+    /** Drag handlers only bubble, starting from the element the left button was pressed on */
+    pub(crate) ondragstart: Handler,
+    pub(crate) ondrag: Handler,
+    pub(crate) ondragend: Handler,
+    pub(crate) onmousemove_bubble: Handler,
+    pub(crate) onmousemove_capture: Handler,
+    /** Not bubbled. Called when the mouse comes over the element or one inside it */
+    pub(crate) onmouseenter: Handler,
+    pub(crate) onmouseleave: Handler,
     pub(crate) onenter:Option<Arc<Mutex<dyn FnMut(&mut EVENT) + 'static>>>, // should be a clousure // should be a clousure
     pub(crate) onfocus:Option<Arc<Mutex<dyn FnMut(&mut EVENT) + 'static>>>, // should be a clousure                  // should be a clousure
     pub(crate) onunfocus:Option<Arc<Mutex<dyn FnMut(&mut EVENT) + 'static>>>, // should be a clousure                // should be a clousure
     pub(crate) render: bool,
     pub(crate) overflow: OVERFLOWBEHAVIOUR,
+    pub(crate) white_space: WHITESPACE,
+    pub(crate) text_align: TEXTALIGN,
+    pub(crate) text_overflow: TEXTOVERFLOW,
+    pub(crate) display: DISPLAY,
+    pub(crate) scrollbar: SCROLLBAR,
+    /** Pseudo-class styles, applied over the rest of the style by the IView according to its state */
+    pub(crate) focus_style: Vec<STYLE>,
+    /** Styles applied while the terminal size is in the range of the Media, in the order given */
+    pub(crate) media_styles: Vec<(Media, Vec<STYLE>)>,
+    pub(crate) hover_style: Vec<STYLE>,
+    pub(crate) active_style: Vec<STYLE>,
 }
 
 unsafe impl Send for Style {}
@@ -258,6 +827,12 @@ pub const FIT_CONTENT: i32 = -1;
 pub const MAX_CONTENT: i32 = -2;
 
 pub const NEWLINE:i32 = '\n' as i32;
+/** Key codes given to Ctrl + Home and Ctrl + End. ncurses has none for them */
+pub const KEY_CTRL_HOME: i32 = 0x1000;
+pub const KEY_CTRL_END: i32 = 0x1001;
+/** Markers around the pasted text in bracketed paste mode */
+pub(crate) const KEY_PASTE_START: i32 = 0x1002;
+pub(crate) const KEY_PASTE_END: i32 = 0x1003;
 
 impl Style {
     pub(crate) fn default() -> Style {
@@ -274,32 +849,77 @@ impl Style {
             margintop: DIMEN::default(),
             marginright: DIMEN::default(),
             marginbottom: DIMEN::default(),
-            border: 0,
-            border_color: -1,
+            border_top: 0,
+            border_right: 0,
+            border_bottom: 0,
+            border_left: 0,
+            border_color: TRANSPARENT,
+            border_top_color: TRANSPARENT,
+            border_right_color: TRANSPARENT,
+            border_bottom_color: TRANSPARENT,
+            border_left_color: TRANSPARENT,
+            border_style: BORDERSTYLE::default(),
+            border_title: None,
+            border_footer: None,
             flex_wrap: false,
             flex_grow: false,
-            color: -1,
-            background_color: -2,
+            stick_to_bottom: false,
+            wheel_step: 1,
+            focus_group: String::new(),
+            color: TRANSPARENT,
+            background_color: TRANSPARENT,
             flex_direction: FLEXDIRECTION::default(),
             boxsizing: BOXSIZING::default(),
             position: POSITION::default(),
             flex: 0,
             taborder: -1,
             z_index: 0,
+            attributes: 0,
+            attributes_set: 0,
             onclick_bubble: None,
             onscroll_bubble: None,
             onclick_capture: None,
             onscroll_capture: None,
+            onkeydown_bubble: None,
+            onkeydown_capture: None,
+            onresize: None,
+            onpaste_bubble: None,
+            onpaste_capture: None,
+            onmousedown_bubble: None,
+            onmousedown_capture: None,
+            onmouseup_bubble: None,
+            onmouseup_capture: None,
+            oncontextmenu_bubble: None,
+            oncontextmenu_capture: None,
+            ondoubleclick_bubble: None,
+            ondoubleclick_capture: None,
+            ondragstart: None,
+            ondrag: None,
+            ondragend: None,
+            onmousemove_bubble: None,
+            onmousemove_capture: None,
+            onmouseenter: None,
+            onmouseleave: None,
             onenter: None,
             onfocus: None,
             onunfocus: None,
             render: true,
             overflow: OVERFLOWBEHAVIOUR::HIDDEN,
+            white_space: WHITESPACE::default(),
+            text_align: TEXTALIGN::default(),
+            text_overflow: TEXTOVERFLOW::default(),
+            display: DISPLAY::default(),
+            scrollbar: SCROLLBAR::default(),
+            focus_style: vec![],
+            media_styles: vec![],
+            hover_style: vec![],
+            active_style: vec![],
         }
     }
     pub(crate) fn set_style(&mut self, v: STYLE) {
         match v {
             STYLE::TABORDER(t) => self.taborder = t,
+            STYLE::FOCUSGROUP(g) => self.focus_group = g,
             STYLE::HIEGHT(h) => self.height = h.verify(),
             STYLE::WIDTH(w) => self.width = w.verify(),
             STYLE::TOP(t) => self.top = t.verify(),
@@ -312,7 +932,23 @@ impl Style {
             STYLE::MARGINTOP(p) => self.margintop = p.verify(),
             STYLE::MARGINRIGHT(p) => self.marginright = p.verify(),
             STYLE::MARGINBOTTOM(p) => self.marginbottom = p.verify(),
-            STYLE::BORDER(b) => self.border = b as i32,
+            STYLE::BORDER(b) => {
+                self.border_top = b as i32;
+                self.border_right = b as i32;
+                self.border_bottom = b as i32;
+                self.border_left = b as i32;
+            }
+            STYLE::BORDERTOP(b) => self.border_top = b as i32,
+            STYLE::BORDERRIGHT(b) => self.border_right = b as i32,
+            STYLE::BORDERBOTTOM(b) => self.border_bottom = b as i32,
+            STYLE::BORDERLEFT(b) => self.border_left = b as i32,
+            STYLE::BORDERTOPCOLOR(c) => self.border_top_color = c,
+            STYLE::BORDERRIGHTCOLOR(c) => self.border_right_color = c,
+            STYLE::BORDERBOTTOMCOLOR(c) => self.border_bottom_color = c,
+            STYLE::BORDERLEFTCOLOR(c) => self.border_left_color = c,
+            STYLE::BORDERSTYLE(b) => self.border_style = b,
+            STYLE::BORDERTITLE(title, align) => self.border_title = Some(Box::new((title, align))),
+            STYLE::BORDERFOOTER(footer, align) => self.border_footer = Some(Box::new((footer, align))),
             STYLE::FLEX(f) => self.flex = f,
             STYLE::FLEXDIRECTION(f) => self.flex_direction = f,
             STYLE::POSITION(f) => self.position = f,
@@ -323,10 +959,96 @@ impl Style {
             STYLE::ZINDEX(z) => self.z_index = z,
             STYLE::FLEXWRAP(f) => self.flex_wrap = f,
             STYLE::FLEXGROW(f) => self.flex_grow = f,
+            STYLE::STICKTOBOTTOM(s) => self.stick_to_bottom = s,
+            STYLE::WHEELSTEP(s) => {
+                if s < 1 {
+                    endwin();
+                    panic!("Invalid Wheel Step: WHEELSTEP() >= 1");
+                }
+                self.wheel_step = s
+            }
             STYLE::OVERFLOW(overflow_behaviour) => self.overflow = overflow_behaviour,
+            STYLE::WHITESPACE(w) => self.white_space = w,
+            STYLE::TEXTALIGN(t) => self.text_align = t,
+            STYLE::TEXTOVERFLOW(t) => self.text_overflow = t,
+            STYLE::DISPLAY(d) => self.display = d,
+            STYLE::SCROLLBAR(s) => self.scrollbar = s,
+            STYLE::BOLD(on) => self.set_attribute(BOLD, on),
+            STYLE::ITALIC(on) => self.set_attribute(ITALIC, on),
+            STYLE::UNDERLINE(on) => self.set_attribute(UNDERLINE, on),
+            STYLE::REVERSE(on) => self.set_attribute(REVERSE, on),
+            STYLE::DIM(on) => self.set_attribute(DIM, on),
+            STYLE::BLINK(on) => self.set_attribute(BLINK, on),
+            STYLE::STRIKETHROUGH(on) => self.set_attribute(STRIKETHROUGH, on),
+            STYLE::FOCUS(styles) => self.focus_style = styles,
+            STYLE::HOVER(styles) => self.hover_style = styles,
+            STYLE::ACTIVE(styles) => self.active_style = styles,
+            STYLE::MEDIA(media, styles) => self.media_styles.push((media, styles)),
         }
     }
 
+    /** width taken by the border along x */
+    pub(crate) fn border_x(&self) -> i32 {
+        self.border_left + self.border_right
+    }
+
+    /** height taken by the border along y */
+    pub(crate) fn border_y(&self) -> i32 {
+        self.border_top + self.border_bottom
+    }
+
+    /** width taken by the border and the vertical scrollbar gutter along x */
+    pub(crate) fn frame_x(&self) -> i32 {
+        self.border_x() + self.scrollbar_width()
+    }
+
+    /** height taken by the border and the horizontal scrollbar gutter along y */
+    pub(crate) fn frame_y(&self) -> i32 {
+        self.border_y() + self.scrollbar_height()
+    }
+
+    pub(crate) fn has_border(&self) -> bool {
+        self.border_top + self.border_right + self.border_bottom + self.border_left > 0
+    }
+
+    /** width of the vertical scrollbar gutter, placed inside the right border */
+    pub(crate) fn scrollbar_width(&self) -> i32 {
+        (matches!(self.overflow, OVERFLOWBEHAVIOUR::SCROLL)
+            && matches!(self.scrollbar, SCROLLBAR::VERTICAL | SCROLLBAR::BOTH)) as i32
+    }
+
+    /** height of the horizontal scrollbar gutter, placed inside the bottom border */
+    pub(crate) fn scrollbar_height(&self) -> i32 {
+        (matches!(self.overflow, OVERFLOWBEHAVIOUR::SCROLL)
+            && matches!(self.scrollbar, SCROLLBAR::HORIZONTAL | SCROLLBAR::BOTH)) as i32
+    }
+
+    fn set_attribute(&mut self, attribute: u32, on: bool) {
+        self.attributes_set |= attribute;
+        if on {
+            self.attributes |= attribute;
+        } else {
+            self.attributes &= !attribute;
+        }
+    }
+
+    /** returns the text attributes after taking the ones which are not given from the parent */
+    pub(crate) fn inherit_attributes(&self, parent_attributes: u32) -> u32 {
+        (parent_attributes & !self.attributes_set) | (self.attributes & self.attributes_set)
+    }
+
+    /** whether some pseudo-class or media style can change this style */
+    pub(crate) fn has_conditional_style(&self) -> bool {
+        !self.focus_style.is_empty()
+            || !self.hover_style.is_empty()
+            || !self.active_style.is_empty()
+            || !self.media_styles.is_empty()
+    }
+
+    pub(crate) fn is_hidden(&self) -> bool {
+        matches!(self.display, DISPLAY::NONE)
+    }
+
     pub(crate) fn from_style(styles: Vec<STYLE>) -> Style {
         let mut style_obj = Style::default();
 
@@ -343,35 +1065,80 @@ impl Style {
      * true: stop_propogation
      */
     pub(crate) fn handle_event(&self, event: &mut EVENT, capture: bool) {
-        let mut fnc_opt: &Option<Arc<Mutex<dyn FnMut(&mut EVENT)>>> = &None;
+        // handlers in the order they are called
+        let mut fncs: Vec<&Handler> = vec![];
         if let Some(mevent) = event.mevent {
-            if mevent.bstate == BUTTON1_PRESSED as u32 || mevent.bstate == BUTTON3_PRESSED as u32 {
-                // left mouse clicked                           // right click
+            if mevent.bstate & (BUTTON1_PRESSED | BUTTON2_PRESSED | BUTTON3_PRESSED) as u32 > 0 {
+                if capture {
+                    fncs.push(&self.onmousedown_capture);
+                } else {
+                    fncs.push(&self.onmousedown_bubble);
+                }
+            }
+            if mevent.bstate & BUTTON1_PRESSED as u32 != 0 {
+                // left mouse clicked
+                if capture {
+                    fncs.push(&self.onclick_capture);
+                } else {
+                    fncs.push(&self.onclick_bubble);
+                }
+            } else if mevent.bstate & BUTTON3_PRESSED as u32 != 0 {
+                // right click
                 if capture {
-                    fnc_opt = &self.onclick_capture;
+                    fncs.push(&self.oncontextmenu_capture);
                 } else {
-                    fnc_opt = &self.onclick_bubble;
+                    fncs.push(&self.oncontextmenu_bubble);
                 }
-            } else if mevent.bstate == BUTTON4_PRESSED as u32
-                || mevent.bstate == BUTTON5_PRESSED as u32
-            {
+            } else if mevent.bstate & (BUTTON4_PRESSED | BUTTON5_PRESSED) as u32 != 0 {
                 // scroll up                                            // scroll down
                 if capture {
-                    fnc_opt = &self.onscroll_capture;
+                    fncs.push(&self.onscroll_capture);
                 } else {
-                    fnc_opt = &self.onscroll_bubble;
+                    fncs.push(&self.onscroll_bubble);
                 }
+            } else if mevent.bstate & (BUTTON1_RELEASED | BUTTON2_RELEASED | BUTTON3_RELEASED) as u32 > 0 {
+                if capture {
+                    fncs.push(&self.onmouseup_capture);
+                } else {
+                    fncs.push(&self.onmouseup_bubble);
+                }
+            } else if mevent.bstate & REPORT_MOUSE_POSITION as u32 > 0 {
+                // mouse moved
+                if capture {
+                    fncs.push(&self.onmousemove_capture);
+                } else {
+                    fncs.push(&self.onmousemove_bubble);
+                }
+            }
+            if event.click_count == 2 {
+                if capture {
+                    fncs.push(&self.ondoubleclick_capture);
+                } else {
+                    fncs.push(&self.ondoubleclick_bubble);
+                }
+            }
+        } else if event.paste.is_some() {
+            if capture {
+                fncs.push(&self.onpaste_capture);
+            } else {
+                fncs.push(&self.onpaste_bubble);
+            }
+        } else if capture {
+            fncs.push(&self.onkeydown_capture);
+        } else {
+            // onkeydown gets every key, onenter runs after it for Enter
+            if let Some(onkeydown) = &self.onkeydown_bubble {
+                onkeydown.lock().unwrap()(event);
             }
-        } else if !capture {
             match event.key {
                 NEWLINE => {
-                    fnc_opt = &self.onenter;
+                    fncs.push(&self.onenter);
                 }
                 _ => {}
             }
         }
 
-        if let Some(fnc) = fnc_opt {
+        for fnc in fncs.into_iter().flatten() {
             fnc.lock().unwrap()(event);
         }
     }
@@ -389,6 +1156,178 @@ impl Default for OVERFLOWBEHAVIOUR {
         OVERFLOWBEHAVIOUR::HIDDEN
     }
 }
+#[derive(Debug, Clone, Copy)]
+pub enum SCROLLBAR {
+    NONE,
+    VERTICAL,
+    HORIZONTAL,
+    BOTH,
+}
+
+impl Default for SCROLLBAR {
+    fn default() -> Self {
+        SCROLLBAR::NONE
+    }
+}
+
+/**
+ * Range of the terminal size in which a style applies, like a media query. Bounds are inclusive, the ones not given do not limit.
+ * Eg: `Media::new().max_width(79)` for terminals narrower than 80 columns
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Media {
+    min_width: Option<i32>,
+    max_width: Option<i32>,
+    min_height: Option<i32>,
+    max_height: Option<i32>,
+}
+
+impl Media {
+    pub fn new() -> Media {
+        Media::default()
+    }
+    /** in columns */
+    pub fn min_width(mut self, columns: i32) -> Self {
+        self.min_width = Some(columns);
+        self
+    }
+    pub fn max_width(mut self, columns: i32) -> Self {
+        self.max_width = Some(columns);
+        self
+    }
+    /** in rows */
+    pub fn min_height(mut self, rows: i32) -> Self {
+        self.min_height = Some(rows);
+        self
+    }
+    pub fn max_height(mut self, rows: i32) -> Self {
+        self.max_height = Some(rows);
+        self
+    }
+
+    /** whether the terminal of the given (rows, columns) is in the range */
+    pub(crate) fn matches(&self, (rows, columns): (i32, i32)) -> bool {
+        self.min_width.is_none_or(|min| columns >= min)
+            && self.max_width.is_none_or(|max| columns <= max)
+            && self.min_height.is_none_or(|min| rows >= min)
+            && self.max_height.is_none_or(|max| rows <= max)
+    }
+}
+
+/** Characters used to draw a border */
+#[derive(Debug, Clone)]
+pub struct BorderChars {
+    pub horizontal: String,
+    pub vertical: String,
+    pub top_left: String,
+    pub top_right: String,
+    pub bottom_left: String,
+    pub bottom_right: String,
+}
+
+impl BorderChars {
+    fn from(chars: [&str; 6]) -> BorderChars {
+        let [horizontal, vertical, top_left, top_right, bottom_left, bottom_right] =
+            chars.map(|c| c.to_string());
+        BorderChars {
+            horizontal,
+            vertical,
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum BORDERSTYLE {
+    SINGLE,
+    DOUBLE,
+    ROUNDED,
+    HEAVY,
+    DASHED,
+    /** For terminals without unicode */
+    ASCII,
+    CUSTOM(BorderChars),
+}
+
+impl Default for BORDERSTYLE {
+    fn default() -> Self {
+        BORDERSTYLE::SINGLE
+    }
+}
+
+impl BORDERSTYLE {
+    pub fn chars(&self) -> BorderChars {
+        match self {
+            BORDERSTYLE::SINGLE => BorderChars::from(["─", "│", "┌", "┐", "└", "┘"]),
+            BORDERSTYLE::DOUBLE => BorderChars::from(["═", "║", "╔", "╗", "╚", "╝"]),
+            BORDERSTYLE::ROUNDED => BorderChars::from(["─", "│", "╭", "╮", "╰", "╯"]),
+            BORDERSTYLE::HEAVY => BorderChars::from(["━", "┃", "┏", "┓", "┗", "┛"]),
+            BORDERSTYLE::DASHED => BorderChars::from(["╌", "╎", "┌", "┐", "└", "┘"]),
+            BORDERSTYLE::ASCII => BorderChars::from(["-", "|", "+", "+", "+", "+"]),
+            BORDERSTYLE::CUSTOM(chars) => chars.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum WHITESPACE {
+    /** Whitespaces and newlines are collapsed into a single space. Wraps at the word boundaries */
+    WRAP,
+    /** Breaks the lines only at newlines */
+    NOWRAP,
+    /** Whitespaces and newlines are kept as they are. Wraps at the word boundaries */
+    PREWRAP,
+}
+
+impl Default for WHITESPACE {
+    fn default() -> Self {
+        WHITESPACE::PREWRAP
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum TEXTALIGN {
+    LEFT,
+    CENTER,
+    RIGHT,
+    /** Last line of a paragraph is aligned left */
+    JUSTIFY,
+}
+
+impl Default for TEXTALIGN {
+    fn default() -> Self {
+        TEXTALIGN::LEFT
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum TEXTOVERFLOW {
+    CLIP,
+    /** Text which does not fit is cut and ends with '…'. Not applied if overflow is SCROLL */
+    ELLIPSIS,
+}
+
+impl Default for TEXTOVERFLOW {
+    fn default() -> Self {
+        TEXTOVERFLOW::CLIP
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum DISPLAY {
+    FLEX,
+    /** Not laid out, rendered or hit by the mouse. Takes no space */
+    NONE,
+}
+
+impl Default for DISPLAY {
+    fn default() -> Self {
+        DISPLAY::FLEX
+    }
+}
 
 #[derive(Debug, Clone, Copy)]
 pub enum FLEXDIRECTION {
@@ -428,6 +1367,7 @@ impl Default for BOXSIZING {
     }
 }
 
+#[derive(Clone)]
 pub enum STYLE {
     HIEGHT(DIMEN),
     WIDTH(DIMEN),
@@ -443,10 +1383,27 @@ pub enum STYLE {
     MARGINRIGHT(DIMEN),
     MARGINBOTTOM(DIMEN),
     TABORDER(i32),
+    /** Arrow keys move the focus among the elements with the same group. Only for elements with TABORDER >= 0 */
+    FOCUSGROUP(String),
     BORDER(bool),
     BACKGROUNDCOLOR(i16),
     TEXTCOLOR(i16),
     BORDERCOLOR(i16),
+    BORDERSTYLE(BORDERSTYLE),
+    /** Each side of the border. BORDER sets all of them */
+    BORDERTOP(bool),
+    BORDERRIGHT(bool),
+    BORDERBOTTOM(bool),
+    BORDERLEFT(bool),
+    /** Color of each side of the border. If not given BORDERCOLOR is used */
+    BORDERTOPCOLOR(i16),
+    BORDERRIGHTCOLOR(i16),
+    BORDERBOTTOMCOLOR(i16),
+    BORDERLEFTCOLOR(i16),
+    /** Caption over the top border. Color and attributes not given in the Span are taken from the border. JUSTIFY is same as LEFT */
+    BORDERTITLE(Span, TEXTALIGN),
+    /** Caption over the bottom border */
+    BORDERFOOTER(Span, TEXTALIGN),
     BOXSIZING(BOXSIZING),
     POSITION(POSITION),
     FLEXWRAP(bool),
@@ -457,4 +1414,32 @@ pub enum STYLE {
     FLEXDIRECTION(FLEXDIRECTION),
     ZINDEX(i32),
     OVERFLOW(OVERFLOWBEHAVIOUR),
+    /** Only for Text. Default PREWRAP */
+    WHITESPACE(WHITESPACE),
+    TEXTALIGN(TEXTALIGN),
+    TEXTOVERFLOW(TEXTOVERFLOW),
+    /** Default FLEX. NONE hides the element along with its children */
+    DISPLAY(DISPLAY),
+    /** Gutter with a scrollbar inside the border. Only when OVERFLOW is SCROLL */
+    SCROLLBAR(SCROLLBAR),
+    /** Stays scrolled to the bottom when the content grows, while the scroll is at the bottom. Only when OVERFLOW is SCROLL */
+    STICKTOBOTTOM(bool),
+    /** Rows or columns scrolled per wheel event. Default 1 */
+    WHEELSTEP(i32),
+    /** Text attributes. If not given, inherited from the parent */
+    BOLD(bool),
+    ITALIC(bool),
+    UNDERLINE(bool),
+    REVERSE(bool),
+    DIM(bool),
+    BLINK(bool),
+    STRIKETHROUGH(bool),
+    /** Styles applied while the element is focused. Changing state only repaints, components are not called again */
+    FOCUS(Vec<STYLE>),
+    /** Styles applied while the mouse is over the element */
+    HOVER(Vec<STYLE>),
+    /** Styles applied while the element is pressed by the mouse */
+    ACTIVE(Vec<STYLE>),
+    /** Styles applied while the terminal size is in the range of the Media. Can be given more than once */
+    MEDIA(Media, Vec<STYLE>),
 }
@@ -0,0 +1,171 @@
+/***
+ * Semantic colors of the app
+ */
+
+use ncurses::{COLOR_BLACK, COLOR_BLUE, COLOR_CYAN, COLOR_GREEN, COLOR_RED, COLOR_WHITE, COLOR_YELLOW};
+
+use crate::styles::TRANSPARENT;
+
+/** Roles can be used in place of a color. They are resolved using the installed theme while rendering */
+pub const PRIMARY: i16 = -10;
+pub const ON_PRIMARY: i16 = -11;
+pub const SECONDARY: i16 = -12;
+pub const ON_SECONDARY: i16 = -13;
+pub const BACKGROUND: i16 = -14;
+pub const ON_BACKGROUND: i16 = -15;
+pub const SURFACE: i16 = -16;
+pub const ON_SURFACE: i16 = -17;
+pub const ERROR: i16 = -18;
+pub const ON_ERROR: i16 = -19;
+pub const WARNING: i16 = -20;
+pub const SUCCESS: i16 = -21;
+pub const MUTED: i16 = -22;
+pub const BORDER: i16 = -23;
+
+/** Terminal's 'bright black' */
+const GREY: i16 = 8;
+/** Terminal's 'bright white' */
+const BRIGHT_WHITE: i16 = 15;
+
+/**
+ * Maps the semantic roles to actual color index.
+ * Install using `run_with_theme` or switch using `set_theme`.
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub primary: i16,
+    pub on_primary: i16,
+    pub secondary: i16,
+    pub on_secondary: i16,
+    pub background: i16,
+    pub on_background: i16,
+    pub surface: i16,
+    pub on_surface: i16,
+    pub error: i16,
+    pub on_error: i16,
+    pub warning: i16,
+    pub success: i16,
+    pub muted: i16,
+    pub border: i16,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::terminal()
+    }
+}
+
+impl Theme {
+    /** Keeps the terminal's own background and text color. Only accents are set */
+    pub const fn terminal() -> Theme {
+        Theme {
+            primary: COLOR_BLUE,
+            on_primary: -1,
+            secondary: COLOR_CYAN,
+            on_secondary: -1,
+            background: -1,
+            on_background: -1,
+            surface: -1,
+            on_surface: -1,
+            error: COLOR_RED,
+            on_error: -1,
+            warning: COLOR_YELLOW,
+            success: COLOR_GREEN,
+            muted: GREY,
+            border: -1,
+        }
+    }
+
+    pub const fn dark() -> Theme {
+        Theme {
+            primary: COLOR_BLUE,
+            on_primary: COLOR_WHITE,
+            secondary: COLOR_CYAN,
+            on_secondary: COLOR_BLACK,
+            background: COLOR_BLACK,
+            on_background: COLOR_WHITE,
+            surface: COLOR_BLACK,
+            on_surface: BRIGHT_WHITE,
+            error: COLOR_RED,
+            on_error: COLOR_WHITE,
+            warning: COLOR_YELLOW,
+            success: COLOR_GREEN,
+            muted: GREY,
+            border: GREY,
+        }
+    }
+
+    pub const fn light() -> Theme {
+        Theme {
+            primary: COLOR_BLUE,
+            on_primary: BRIGHT_WHITE,
+            secondary: COLOR_CYAN,
+            on_secondary: COLOR_BLACK,
+            background: BRIGHT_WHITE,
+            on_background: COLOR_BLACK,
+            surface: COLOR_WHITE,
+            on_surface: COLOR_BLACK,
+            error: COLOR_RED,
+            on_error: BRIGHT_WHITE,
+            warning: COLOR_YELLOW,
+            success: COLOR_GREEN,
+            muted: GREY,
+            border: COLOR_BLACK,
+        }
+    }
+
+    /**
+     * returns the color index for the given role.
+     * if color is not a role it is returned as it is
+     */
+    pub fn resolve(&self, color: i16) -> i16 {
+        match color {
+            PRIMARY => self.primary,
+            ON_PRIMARY => self.on_primary,
+            SECONDARY => self.secondary,
+            ON_SECONDARY => self.on_secondary,
+            BACKGROUND => self.background,
+            ON_BACKGROUND => self.on_background,
+            SURFACE => self.surface,
+            ON_SURFACE => self.on_surface,
+            ERROR => self.error,
+            ON_ERROR => self.on_error,
+            WARNING => self.warning,
+            SUCCESS => self.success,
+            MUTED => self.muted,
+            BORDER => self.border,
+            _ => color,
+        }
+    }
+
+    /** resolves the color, taking the parent's when it is TRANSPARENT */
+    pub(crate) fn inherit(&self, color: i16, parent: i16) -> i16 {
+        if color == TRANSPARENT {
+            parent
+        } else {
+            self.resolve(color)
+        }
+    }
+
+    /** returns the role for the given name. Eg: "primary", "on-primary" */
+    pub fn role(name: &str) -> Option<i16> {
+        let role = match name.trim().replace('_', "-").as_str() {
+            "primary" => PRIMARY,
+            "on-primary" => ON_PRIMARY,
+            "secondary" => SECONDARY,
+            "on-secondary" => ON_SECONDARY,
+            "background" => BACKGROUND,
+            "on-background" => ON_BACKGROUND,
+            "surface" => SURFACE,
+            "on-surface" => ON_SURFACE,
+            "error" => ERROR,
+            "on-error" => ON_ERROR,
+            "warning" => WARNING,
+            "success" => SUCCESS,
+            "muted" => MUTED,
+            "border" => BORDER,
+            _ => return None,
+        };
+        Some(role)
+    }
+}